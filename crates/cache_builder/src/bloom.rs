@@ -0,0 +1,72 @@
+// A thread-safe, fixed-size bloom filter used to deduplicate contract
+// bytecode during streaming without holding every byte string seen so far
+// in memory. Unlike a `HashSet` that must be periodically cleared once it
+// grows too large (silently forgetting what it has seen), the filter's
+// memory footprint is bounded up front by the expected item count and
+// target false-positive rate.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use tiny_keccak::{Hasher, Keccak};
+
+pub struct BloomFilter {
+    bits: Vec<AtomicU64>,
+    m: usize,
+    k: usize,
+}
+
+impl BloomFilter {
+    /// Sizes the bit array from the expected number of items and a target
+    /// false-positive rate, per the standard bloom filter formulas:
+    /// `m = -n*ln(p) / (ln 2)^2` bits and `k = (m/n)*ln 2` hash functions.
+    pub fn new(expected_items: usize, false_positive_rate: f64) -> Self {
+        let n = expected_items.max(1) as f64;
+        let m = (-(n * false_positive_rate.ln()) / std::f64::consts::LN_2.powi(2))
+            .ceil()
+            .max(64.0) as usize;
+        let k = (((m as f64 / n) * std::f64::consts::LN_2).round() as usize).max(1);
+        let words = m.div_ceil(64);
+
+        BloomFilter {
+            bits: (0..words).map(|_| AtomicU64::new(0)).collect(),
+            m,
+            k,
+        }
+    }
+
+    // Derives the two base hashes from a single keccak256 digest of `item`:
+    // the digest's first 8 bytes form a 64-bit hash, whose upper and lower
+    // halves become `h1` and `h2`. The `k` bit positions are then
+    // `h1 + i*h2 mod m` (double hashing), avoiding `k` independent hashes.
+    fn positions(&self, item: &[u8]) -> impl Iterator<Item = usize> + '_ {
+        let mut hasher = Keccak::v256();
+        hasher.update(item);
+        let mut digest = [0u8; 32];
+        hasher.finalize(&mut digest);
+
+        let hash64 = u64::from_le_bytes(digest[0..8].try_into().unwrap());
+        let h1 = (hash64 >> 32) as usize;
+        let h2 = (hash64 & 0xFFFF_FFFF) as usize;
+        let m = self.m;
+
+        (0..self.k).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % m)
+    }
+
+    /// Checks whether `item` is a probable duplicate and unconditionally
+    /// sets its bits. Returns `true` if every bit was already set (probable
+    /// duplicate - callers should still confirm with an exact check, since
+    /// this can false-positive but never false-negative).
+    pub fn check_and_set(&self, item: &[u8]) -> bool {
+        let mut all_set = true;
+
+        for pos in self.positions(item) {
+            let word = pos / 64;
+            let mask = 1u64 << (pos % 64);
+            let old = self.bits[word].fetch_or(mask, Ordering::Relaxed);
+            if old & mask == 0 {
+                all_set = false;
+            }
+        }
+
+        all_set
+    }
+}
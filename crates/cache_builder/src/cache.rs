@@ -1,19 +1,92 @@
 use blake3;
 use eyre::Result;
 use lmdb::{Database, Environment, EnvironmentFlags, Transaction, WriteFlags};
+use lru::LruCache;
+use std::num::NonZeroUsize;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::{env, fs};
 
 use crate::types::ABI;
 
+// Header prefixed to every value written by `put`/`put_batch`, so a
+// `bincode` layout change can be detected on read instead of silently
+// producing garbage `ABI` values. Bump `CACHE_SCHEMA_VERSION` whenever the
+// `ABI` wire format changes - old entries then degrade gracefully to a
+// cache miss (recompute-on-miss) rather than corrupting the read.
+const CACHE_ENTRY_MAGIC: [u8; 4] = *b"HAB1";
+// v2: `StorageSlot.index` widened from `u64` to a lossless decimal `String`,
+// plus the new `kind`/`mapping_key_type` fields.
+const CACHE_SCHEMA_VERSION: u16 = 2;
+const CACHE_ENTRY_HEADER_LEN: usize = CACHE_ENTRY_MAGIC.len() + 2;
+
+fn encode_entry(abi: &ABI) -> Result<Vec<u8>> {
+    let payload = bincode::serialize(abi)?;
+    let mut entry = Vec::with_capacity(CACHE_ENTRY_HEADER_LEN + payload.len());
+    entry.extend_from_slice(&CACHE_ENTRY_MAGIC);
+    entry.extend_from_slice(&CACHE_SCHEMA_VERSION.to_le_bytes());
+    entry.extend_from_slice(&payload);
+    Ok(entry)
+}
+
+fn entry_header_is_current(bytes: &[u8]) -> bool {
+    bytes.len() >= CACHE_ENTRY_HEADER_LEN
+        && bytes[..CACHE_ENTRY_MAGIC.len()] == CACHE_ENTRY_MAGIC
+        && u16::from_le_bytes([bytes[CACHE_ENTRY_MAGIC.len()], bytes[CACHE_ENTRY_MAGIC.len() + 1]])
+            == CACHE_SCHEMA_VERSION
+}
+
+fn decode_entry(bytes: &[u8]) -> Option<ABI> {
+    if !entry_header_is_current(bytes) {
+        return None;
+    }
+    bincode::deserialize(&bytes[CACHE_ENTRY_HEADER_LEN..]).ok()
+}
+
+// Rough average size of a decoded ABI once deserialized, used to translate an
+// approximate byte budget into an LRU entry count.
+const ESTIMATED_ABI_BYTES: usize = 2048;
+
+// Default number of hot `Arc<ABI>` entries kept in the in-memory L1 layer.
+const DEFAULT_L1_CAPACITY: usize = 10_000;
+
+/// How the in-memory L1 cache's size should be chosen.
+#[derive(Clone, Copy, Debug)]
+pub enum L1Capacity {
+    /// Keep at most this many entries, regardless of their size.
+    Entries(usize),
+    /// Keep roughly this many bytes of decoded `ABI`s, using a fixed
+    /// per-entry size estimate to derive an entry count.
+    ApproxBytes(usize),
+}
+
+impl L1Capacity {
+    fn entries(self) -> usize {
+        match self {
+            L1Capacity::Entries(n) => n,
+            L1Capacity::ApproxBytes(bytes) => (bytes / ESTIMATED_ABI_BYTES).max(1),
+        }
+    }
+}
+
+impl Default for L1Capacity {
+    fn default() -> Self {
+        L1Capacity::Entries(DEFAULT_L1_CAPACITY)
+    }
+}
+
 // Statistics tracking
 pub struct CacheStats {
     pub hits: AtomicU64,
     pub misses: AtomicU64,
     pub writes: AtomicU64,
     pub errors: AtomicU64,
+    pub l1_hits: AtomicU64,
+    pub l1_misses: AtomicU64,
+    // Entries whose header magic/version didn't match the compiled-in
+    // schema - treated as misses rather than corrupt reads.
+    pub stale: AtomicU64,
 }
 
 impl CacheStats {
@@ -23,6 +96,9 @@ impl CacheStats {
             misses: AtomicU64::new(0),
             writes: AtomicU64::new(0),
             errors: AtomicU64::new(0),
+            l1_hits: AtomicU64::new(0),
+            l1_misses: AtomicU64::new(0),
+            stale: AtomicU64::new(0),
         }
     }
 }
@@ -32,10 +108,15 @@ pub struct AbiCache {
     env: Arc<Environment>,
     db: Database,
     pub stats: Arc<CacheStats>,
+    l1: Arc<Mutex<LruCache<Vec<u8>, Arc<ABI>>>>,
 }
 
 impl AbiCache {
     pub fn new(directory: Option<PathBuf>) -> Result<Self> {
+        Self::with_l1_capacity(directory, L1Capacity::default())
+    }
+
+    pub fn with_l1_capacity(directory: Option<PathBuf>, l1_capacity: L1Capacity) -> Result<Self> {
         let cache_dir = directory.unwrap_or_else(get_default_cache_dir);
 
         fs::create_dir_all(&cache_dir)?;
@@ -52,10 +133,13 @@ impl AbiCache {
 
         let db = env.open_db(None)?;
 
+        let capacity = NonZeroUsize::new(l1_capacity.entries()).unwrap();
+
         Ok(AbiCache {
             env: Arc::new(env),
             db,
             stats: Arc::new(CacheStats::new()),
+            l1: Arc::new(Mutex::new(LruCache::new(capacity))),
         })
     }
 
@@ -78,15 +162,24 @@ impl AbiCache {
         let key = Self::generate_cache_key(bytecode, skip_resolving);
 
         match self.env.begin_ro_txn() {
-            Ok(txn) => {
-                let result = txn.get(self.db, &key).is_ok();
-                if result {
+            Ok(txn) => match txn.get(self.db, &key) {
+                Ok(bytes) if entry_header_is_current(bytes) => {
                     self.stats.hits.fetch_add(1, Ordering::Relaxed);
-                } else {
+                    true
+                }
+                Ok(_) => {
+                    // Header magic/version mismatch - a stale entry from an
+                    // older wire format. Treat as a miss rather than handing
+                    // back a struct decoded from the wrong layout.
+                    self.stats.stale.fetch_add(1, Ordering::Relaxed);
                     self.stats.misses.fetch_add(1, Ordering::Relaxed);
+                    false
                 }
-                result
-            }
+                Err(_) => {
+                    self.stats.misses.fetch_add(1, Ordering::Relaxed);
+                    false
+                }
+            },
             Err(_) => {
                 self.stats.errors.fetch_add(1, Ordering::Relaxed);
                 false
@@ -94,32 +187,90 @@ impl AbiCache {
         }
     }
 
+    /// Look up a cached `ABI`, checking the in-memory L1 layer before falling
+    /// back to LMDB. A LMDB hit is deserialized once and promoted into the
+    /// L1 layer so subsequent lookups for the same bytecode skip both the
+    /// transaction and the `bincode::deserialize` call.
+    pub fn get(&self, bytecode: &str, skip_resolving: bool) -> Option<Arc<ABI>> {
+        let key = Self::generate_cache_key(bytecode, skip_resolving);
+
+        if let Some(abi) = self.l1.lock().unwrap().get(&key).cloned() {
+            self.stats.l1_hits.fetch_add(1, Ordering::Relaxed);
+            self.stats.hits.fetch_add(1, Ordering::Relaxed);
+            return Some(abi);
+        }
+        self.stats.l1_misses.fetch_add(1, Ordering::Relaxed);
+
+        let txn = match self.env.begin_ro_txn() {
+            Ok(txn) => txn,
+            Err(_) => {
+                self.stats.errors.fetch_add(1, Ordering::Relaxed);
+                return None;
+            }
+        };
+
+        let bytes = match txn.get(self.db, &key) {
+            Ok(bytes) => bytes,
+            Err(_) => {
+                self.stats.misses.fetch_add(1, Ordering::Relaxed);
+                return None;
+            }
+        };
+
+        if !entry_header_is_current(bytes) {
+            self.stats.stale.fetch_add(1, Ordering::Relaxed);
+            self.stats.misses.fetch_add(1, Ordering::Relaxed);
+            return None;
+        }
+
+        match decode_entry(bytes) {
+            Some(abi) => {
+                self.stats.hits.fetch_add(1, Ordering::Relaxed);
+                let abi = Arc::new(abi);
+                self.l1.lock().unwrap().put(key, abi.clone());
+                Some(abi)
+            }
+            None => {
+                self.stats.errors.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+        }
+    }
+
     pub fn put(&self, bytecode: &str, skip_resolving: bool, abi: &ABI) -> Result<()> {
         let key = Self::generate_cache_key(bytecode, skip_resolving);
 
-        // Serialize exactly as Python bindings do
-        let serialized = bincode::serialize(abi)?;
+        let entry = encode_entry(abi)?;
 
         let mut txn = self.env.begin_rw_txn()?;
-        txn.put(self.db, &key, &serialized, WriteFlags::empty())?;
+        txn.put(self.db, &key, &entry, WriteFlags::empty())?;
         txn.commit()?;
 
         self.stats.writes.fetch_add(1, Ordering::Relaxed);
+        self.l1.lock().unwrap().put(key, Arc::new(abi.clone()));
         Ok(())
     }
 
     // Batch write for better performance
     pub fn put_batch(&self, items: Vec<(String, bool, ABI)>) -> Result<()> {
         let mut txn = self.env.begin_rw_txn()?;
+        let mut l1_updates = Vec::with_capacity(items.len());
 
         for (bytecode, skip_resolving, abi) in items {
             let key = Self::generate_cache_key(&bytecode, skip_resolving);
-            let serialized = bincode::serialize(&abi)?;
-            txn.put(self.db, &key, &serialized, WriteFlags::empty())?;
+            let entry = encode_entry(&abi)?;
+            txn.put(self.db, &key, &entry, WriteFlags::empty())?;
             self.stats.writes.fetch_add(1, Ordering::Relaxed);
+            l1_updates.push((key, Arc::new(abi)));
         }
 
         txn.commit()?;
+
+        let mut l1 = self.l1.lock().unwrap();
+        for (key, abi) in l1_updates {
+            l1.put(key, abi);
+        }
+
         Ok(())
     }
 
@@ -132,11 +283,16 @@ impl AbiCache {
 
         eprintln!("DEBUG: Cache cleared");
 
+        self.l1.lock().unwrap().clear();
+
         // Reset stats
         self.stats.hits.store(0, Ordering::Relaxed);
         self.stats.misses.store(0, Ordering::Relaxed);
         self.stats.writes.store(0, Ordering::Relaxed);
         self.stats.errors.store(0, Ordering::Relaxed);
+        self.stats.l1_hits.store(0, Ordering::Relaxed);
+        self.stats.l1_misses.store(0, Ordering::Relaxed);
+        self.stats.stale.store(0, Ordering::Relaxed);
 
         Ok(())
     }
@@ -146,6 +302,9 @@ impl AbiCache {
         let misses = self.stats.misses.load(Ordering::Relaxed);
         let writes = self.stats.writes.load(Ordering::Relaxed);
         let errors = self.stats.errors.load(Ordering::Relaxed);
+        let l1_hits = self.stats.l1_hits.load(Ordering::Relaxed);
+        let l1_misses = self.stats.l1_misses.load(Ordering::Relaxed);
+        let stale = self.stats.stale.load(Ordering::Relaxed);
 
         let total_requests = hits + misses;
         let hit_rate = if total_requests > 0 {
@@ -154,9 +313,16 @@ impl AbiCache {
             0.0
         };
 
+        let l1_total = l1_hits + l1_misses;
+        let l1_hit_rate = if l1_total > 0 {
+            (l1_hits as f64 / l1_total as f64) * 100.0
+        } else {
+            0.0
+        };
+
         format!(
-            "Cache: {} hits, {} misses ({:.1}% hit rate), {} writes, {} errors",
-            hits, misses, hit_rate, writes, errors
+            "Cache: {} hits, {} misses ({:.1}% hit rate), {} writes, {} errors, {} stale | L1: {} hits, {} misses ({:.1}% hit rate)",
+            hits, misses, hit_rate, writes, errors, stale, l1_hits, l1_misses, l1_hit_rate
         )
     }
 }
@@ -0,0 +1,41 @@
+use storage_layout_extractor::extractor::chain::{version::EthereumVersion, Chain};
+
+/// Which chain/EVM fork `ContractProcessor` analyzes storage layouts
+/// against. Only Ethereum mainnet forks are exposed today since `Chain` is
+/// the only variant `storage_layout_extractor` currently supports - this is
+/// the seam non-mainnet chains would plug into as the extractor grows more
+/// `Chain` variants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChainSpec {
+    Ethereum(EthereumVersion),
+}
+
+impl ChainSpec {
+    pub fn shanghai() -> Self {
+        ChainSpec::Ethereum(EthereumVersion::Shanghai)
+    }
+
+    pub fn cancun() -> Self {
+        ChainSpec::Ethereum(EthereumVersion::Cancun)
+    }
+
+    /// Latest fork the extractor recognizes - Cancun, so transient-storage
+    /// contracts (`TSTORE`/`TLOAD`) aren't flagged as using unknown opcodes.
+    pub fn latest() -> Self {
+        ChainSpec::cancun()
+    }
+}
+
+impl Default for ChainSpec {
+    fn default() -> Self {
+        ChainSpec::latest()
+    }
+}
+
+impl From<ChainSpec> for Chain {
+    fn from(spec: ChainSpec) -> Chain {
+        match spec {
+            ChainSpec::Ethereum(version) => Chain::Ethereum { version },
+        }
+    }
+}
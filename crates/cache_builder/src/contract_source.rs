@@ -0,0 +1,298 @@
+// Abstracts over where raw contract bytecode comes from, so the streaming
+// pipeline in `stream_processor` isn't hard-wired to parquet exports.
+
+use crate::parquet_reader::{Contract, ParquetReader};
+use crossbeam_channel::Sender;
+use eyre::{bail, Result};
+use rlp::Rlp;
+use rocksdb::{IteratorMode, Options, DB};
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use tiny_keccak::{Hasher, Keccak};
+use tracing::{debug, warn};
+
+/// A source of contracts to decompile. Implementations push every contract
+/// they have into `sender`, blocking until they're exhausted or the
+/// receiving end hangs up.
+pub trait ContractSource: Send + Sync {
+    fn stream(&self, sender: Sender<Contract>) -> Result<()>;
+}
+
+/// Streams contracts out of a directory of parquet exports, recursing into
+/// partitioned subdirectories (e.g. `chain=.../block_range=.../*.parquet`).
+pub struct ParquetSource {
+    directory: PathBuf,
+    pattern: Option<String>,
+}
+
+impl ParquetSource {
+    pub fn new(directory: PathBuf) -> Self {
+        ParquetSource {
+            directory,
+            pattern: None,
+        }
+    }
+
+    /// Restrict discovery to filenames matching a glob `pattern` (`*`/`?`
+    /// wildcards), e.g. `"ethereum__contracts__148*"`. Only the filename is
+    /// matched, not the partition directories above it.
+    pub fn with_pattern(mut self, pattern: impl Into<String>) -> Self {
+        self.pattern = Some(pattern.into());
+        self
+    }
+}
+
+impl ContractSource for ParquetSource {
+    fn stream(&self, sender: Sender<Contract>) -> Result<()> {
+        for contract in ParquetReader::read_all_parquets(&self.directory, self.pattern.as_deref())? {
+            if sender.send(contract).is_err() {
+                debug!("Worker channels closed, stopping parquet source");
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+// How many addresses to pack into a single JSON-RPC batch request.
+const ETH_GET_CODE_BATCH_SIZE: usize = 50;
+
+/// Streams contracts by batching `eth_getCode` calls against a live
+/// Ethereum JSON-RPC node, for decompiling directly from a node instead of
+/// a pre-exported parquet dataset.
+pub struct RpcSource {
+    rpc_url: String,
+    addresses: Vec<String>,
+    client: reqwest::blocking::Client,
+}
+
+impl RpcSource {
+    pub fn new(rpc_url: String, addresses: Vec<String>) -> Self {
+        RpcSource {
+            rpc_url,
+            addresses,
+            client: reqwest::blocking::Client::new(),
+        }
+    }
+
+    fn batch_get_code(&self, addresses: &[String]) -> Result<Vec<(String, String)>> {
+        let batch: Vec<Value> = addresses
+            .iter()
+            .enumerate()
+            .map(|(i, address)| {
+                json!({
+                    "jsonrpc": "2.0",
+                    "id": i,
+                    "method": "eth_getCode",
+                    "params": [address, "latest"],
+                })
+            })
+            .collect();
+
+        let response: Vec<Value> = self
+            .client
+            .post(&self.rpc_url)
+            .json(&batch)
+            .send()?
+            .json()?;
+
+        // JSON-RPC 2.0 batch responses aren't required to preserve request
+        // order, so correlate each entry back to its address by `id`
+        // instead of assuming `response[i]` answers `addresses[i]`.
+        let by_id: std::collections::HashMap<usize, &Value> = response
+            .iter()
+            .filter_map(|entry| Some((entry.get("id")?.as_u64()? as usize, entry)))
+            .collect();
+
+        let mut results = Vec::with_capacity(addresses.len());
+        for (i, address) in addresses.iter().enumerate() {
+            let Some(entry) = by_id.get(&i) else {
+                warn!("eth_getCode response missing entry for {}", address);
+                continue;
+            };
+
+            if let Some(error) = entry.get("error") {
+                warn!("eth_getCode failed for {}: {:?}", address, error);
+                continue;
+            }
+
+            let code = entry
+                .get("result")
+                .and_then(|v| v.as_str())
+                .unwrap_or("0x")
+                .to_string();
+            results.push((address.clone(), code));
+        }
+
+        Ok(results)
+    }
+}
+
+impl ContractSource for RpcSource {
+    fn stream(&self, sender: Sender<Contract>) -> Result<()> {
+        for chunk in self.addresses.chunks(ETH_GET_CODE_BATCH_SIZE) {
+            let codes = self.batch_get_code(chunk)?;
+
+            for (address, code) in codes {
+                // Skip EOAs / not-yet-deployed addresses.
+                if code.is_empty() || code == "0x" {
+                    continue;
+                }
+
+                let contract = Contract { address, code };
+                if sender.send(contract).is_err() {
+                    debug!("Worker channels closed, stopping RPC source");
+                    return Ok(());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// ---- on-disk state database source --------------------------------------
+
+// go-ethereum `rawdb` key prefixes (see `core/rawdb/schema.go`): the flat
+// account snapshot is keyed by `'a' || keccak256(address)`, contract code
+// blobs are keyed by `'c' || codeHash`, and (when `--cache.preimages` is
+// enabled) address preimages are keyed by `"secure-key-" || keccak256(address)`.
+const SNAPSHOT_ACCOUNT_PREFIX: u8 = b'a';
+const CODE_PREFIX: u8 = b'c';
+const SECURE_KEY_PREFIX: &[u8] = b"secure-key-";
+
+fn keccak256(bytes: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak::v256();
+    hasher.update(bytes);
+    let mut output = [0u8; 32];
+    hasher.finalize(&mut output);
+    output
+}
+
+fn empty_code_hash() -> [u8; 32] {
+    keccak256(&[])
+}
+
+// A "slim" snapshot account record: `[nonce, balance, root, codeHash]`,
+// where `root`/`codeHash` are the empty string when they equal the empty
+// trie root / empty code hash respectively. We only need `codeHash` here.
+fn decode_slim_account_code_hash(rlp_bytes: &[u8]) -> Result<Option<[u8; 32]>> {
+    let rlp = Rlp::new(rlp_bytes);
+    if rlp.item_count()? < 4 {
+        bail!("malformed account RLP: expected 4 fields, got {}", rlp.item_count()?);
+    }
+
+    let code_hash_field: Vec<u8> = rlp.at(3)?.data()?.to_vec();
+    match code_hash_field.len() {
+        0 => Ok(None),
+        32 => {
+            let mut hash = [0u8; 32];
+            hash.copy_from_slice(&code_hash_field);
+            Ok(Some(hash))
+        }
+        n => bail!("malformed codeHash field: expected 32 bytes, got {}", n),
+    }
+}
+
+/// Reads contracts directly out of a synced Ethereum client's on-disk state
+/// database, bypassing the parquet export step entirely. Walks go-ethereum's
+/// flat account snapshot - a separate, optional structure kept alongside the
+/// secure state trie rather than the trie itself, so it can be absent or
+/// stale on a node that hasn't enabled/warmed snapshots (`stream` errors
+/// out rather than silently yielding nothing in that case). Decodes each
+/// account's RLP to recover its `codeHash`, skips EOAs (whose `codeHash` is
+/// the empty-code hash), and resolves the code blob from the code column.
+/// Accounts are keyed by `keccak256(address)` rather than the address
+/// itself, so the original address is only recoverable when the node
+/// recorded preimages; otherwise the account hash is used as a fallback
+/// identifier.
+pub struct StateDbSource {
+    db: DB,
+    datadir: PathBuf,
+}
+
+impl StateDbSource {
+    pub fn open(datadir: &Path) -> Result<Self> {
+        let mut opts = Options::default();
+        opts.create_if_missing(false);
+        let db = DB::open_for_read_only(&opts, datadir, false)?;
+        Ok(StateDbSource {
+            db,
+            datadir: datadir.to_path_buf(),
+        })
+    }
+
+    fn resolve_address(&self, account_hash: &[u8; 32]) -> String {
+        let mut preimage_key = SECURE_KEY_PREFIX.to_vec();
+        preimage_key.extend_from_slice(account_hash);
+
+        match self.db.get(&preimage_key) {
+            Ok(Some(preimage)) if preimage.len() == 20 => format!("0x{}", hex::encode(preimage)),
+            _ => format!("0x{}", hex::encode(account_hash)),
+        }
+    }
+}
+
+impl ContractSource for StateDbSource {
+    fn stream(&self, sender: Sender<Contract>) -> Result<()> {
+        let empty_hash = empty_code_hash();
+        let mode = IteratorMode::From(&[SNAPSHOT_ACCOUNT_PREFIX], rocksdb::Direction::Forward);
+        let mut saw_any_account = false;
+
+        for item in self.db.iterator(mode) {
+            let (key, value) = item?;
+
+            // `iterator(From(...))` keeps scanning past the prefix once
+            // exhausted - stop once we leave the account-snapshot range.
+            if key.first() != Some(&SNAPSHOT_ACCOUNT_PREFIX) || key.len() != 33 {
+                break;
+            }
+            saw_any_account = true;
+
+            let code_hash = match decode_slim_account_code_hash(&value) {
+                Ok(Some(hash)) if hash != empty_hash => hash,
+                Ok(_) => continue, // EOA, or an account with no code
+                Err(e) => {
+                    debug!("Skipping malformed account record: {}", e);
+                    continue;
+                }
+            };
+
+            let mut code_key = vec![CODE_PREFIX];
+            code_key.extend_from_slice(&code_hash);
+
+            let code = match self.db.get(&code_key) {
+                Ok(Some(bytes)) => bytes,
+                Ok(None) => {
+                    debug!("Missing code blob for hash 0x{}", hex::encode(code_hash));
+                    continue;
+                }
+                Err(e) => {
+                    warn!("Failed to read code blob for hash 0x{}: {}", hex::encode(code_hash), e);
+                    continue;
+                }
+            };
+
+            let account_hash: [u8; 32] = key[1..].try_into().unwrap();
+            let contract = Contract {
+                address: self.resolve_address(&account_hash),
+                code: format!("0x{}", hex::encode(code)),
+            };
+
+            if sender.send(contract).is_err() {
+                debug!("Worker channels closed, stopping state-db source");
+                break;
+            }
+        }
+
+        if !saw_any_account {
+            bail!(
+                "no account snapshot rows found under the 'a' prefix in {:?} - this node likely \
+                 hasn't enabled/warmed go-ethereum's flat state snapshot (it's optional and \
+                 separate from the secure state trie), so there is nothing to stream from it",
+                self.datadir
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,549 @@
+// Decodes calldata and event logs against a recovered `ABI`, implementing the
+// standard Solidity head/tail ABI encoding: static types occupy one 32-byte
+// head word each, dynamic types (`bytes`/`string`/`T[]`/dynamic tuples) store
+// a 32-byte offset in the head pointing into a tail region.
+
+use crate::types::{ABI, ABIEvent, ABIEventParam, ABIFunction};
+use eyre::{bail, Result};
+use serde_json::Value;
+use tiny_keccak::{Hasher, Keccak};
+
+/// A calldata decode, with arguments in declaration order.
+pub struct DecodedCall {
+    pub selector: [u8; 4],
+    pub function: ABIFunction,
+    pub args: Vec<(String, Value)>,
+}
+
+impl DecodedCall {
+    pub fn as_json(&self) -> Value {
+        args_to_json(&self.args)
+    }
+}
+
+/// An event log decode, with arguments in declaration order.
+pub struct DecodedLog {
+    pub topic0: [u8; 32],
+    pub event: ABIEvent,
+    pub args: Vec<(String, Value)>,
+}
+
+impl DecodedLog {
+    pub fn as_json(&self) -> Value {
+        args_to_json(&self.args)
+    }
+}
+
+fn args_to_json(args: &[(String, Value)]) -> Value {
+    let mut map = serde_json::Map::with_capacity(args.len());
+    for (name, value) in args {
+        map.insert(name.clone(), value.clone());
+    }
+    Value::Object(map)
+}
+
+/// Decode raw calldata (including its leading 4-byte selector) against the
+/// function it matches via `abi.by_selector`.
+pub fn decode_calldata(abi: &ABI, calldata: &[u8]) -> Result<DecodedCall> {
+    if calldata.len() < 4 {
+        bail!(
+            "calldata is {} bytes, shorter than the 4-byte selector",
+            calldata.len()
+        );
+    }
+
+    let selector: [u8; 4] = calldata[..4].try_into().unwrap();
+    let &idx = abi
+        .by_selector
+        .get(&selector)
+        .ok_or_else(|| eyre::eyre!("no function matches selector 0x{}", hex::encode(selector)))?;
+    let function = &abi.functions[idx];
+
+    let types: Vec<AbiType> = function
+        .input_types
+        .iter()
+        .map(|t| parse_type(t))
+        .collect::<Result<_>>()?;
+
+    let body = &calldata[4..];
+    let values = decode_head_tail(&types, body, 0)?;
+
+    let args = function
+        .inputs
+        .iter()
+        .zip(values)
+        .enumerate()
+        .map(|(i, (param, value))| (arg_name(&param.name, i), value))
+        .collect();
+
+    Ok(DecodedCall {
+        selector,
+        function: function.clone(),
+        args,
+    })
+}
+
+/// Decode the return data of a call against a function's `outputs`.
+pub fn decode_function_output(function: &ABIFunction, data: &[u8]) -> Result<Vec<(String, Value)>> {
+    let types: Vec<AbiType> = function
+        .output_types
+        .iter()
+        .map(|t| parse_type(t))
+        .collect::<Result<_>>()?;
+
+    let values = decode_head_tail(&types, data, 0)?;
+
+    Ok(function
+        .outputs
+        .iter()
+        .zip(values)
+        .enumerate()
+        .map(|(i, (param, value))| (arg_name(&param.name, i), value))
+        .collect())
+}
+
+/// Decode an event log's `topics` + `data` against the event it matches via
+/// `topics[0]`. Indexed dynamic parameters cannot be recovered (the chain
+/// only stores their keccak256 hash in the topic), so they are surfaced as
+/// the raw 32-byte hash.
+pub fn decode_log(abi: &ABI, topics: &[[u8; 32]], data: &[u8]) -> Result<DecodedLog> {
+    let topic0 = *topics
+        .first()
+        .ok_or_else(|| eyre::eyre!("log has no topics to match against topic0"))?;
+
+    let event = abi
+        .events
+        .iter()
+        .find(|e| !e.anonymous && event_topic0(e) == topic0)
+        .ok_or_else(|| eyre::eyre!("no event matches topic0 0x{}", hex::encode(topic0)))?;
+
+    let indexed_count = event.inputs.iter().filter(|p| p.indexed).count();
+    if indexed_count != topics.len() - 1 {
+        bail!(
+            "event {} expects {} indexed topics, log has {}",
+            event.name,
+            indexed_count,
+            topics.len() - 1
+        );
+    }
+
+    let non_indexed_types: Vec<AbiType> = event
+        .inputs
+        .iter()
+        .filter(|p| !p.indexed)
+        .map(|p| parse_type(&p.type_))
+        .collect::<Result<_>>()?;
+    let non_indexed_values = decode_head_tail(&non_indexed_types, data, 0)?;
+    let mut non_indexed_values = non_indexed_values.into_iter();
+
+    let mut topic_idx = 1;
+    let mut args = Vec::with_capacity(event.inputs.len());
+    for (i, param) in event.inputs.iter().enumerate() {
+        let value = if param.indexed {
+            let word = topics[topic_idx];
+            topic_idx += 1;
+            decode_indexed_topic(param, &word)?
+        } else {
+            non_indexed_values.next().expect("decoded one value per non-indexed param")
+        };
+        args.push((arg_name(&param.name, i), value));
+    }
+
+    Ok(DecodedLog {
+        topic0,
+        event: event.clone(),
+        args,
+    })
+}
+
+fn decode_indexed_topic(param: &ABIEventParam, word: &[u8; 32]) -> Result<Value> {
+    let typ = parse_type(&param.type_)?;
+    if is_dynamic(&typ) {
+        // Dynamic indexed params are stored as keccak256(value) in the topic
+        // and cannot be recovered; surface the raw hash instead.
+        Ok(Value::String(format!("0x{}", hex::encode(word))))
+    } else {
+        Ok(decode_static(&typ, word, 0)?.0)
+    }
+}
+
+fn arg_name(name: &str, index: usize) -> String {
+    if name.is_empty() {
+        format!("arg{}", index)
+    } else {
+        name.to_string()
+    }
+}
+
+fn event_topic0(event: &ABIEvent) -> [u8; 32] {
+    let signature = format!(
+        "{}({})",
+        event.name,
+        event
+            .inputs
+            .iter()
+            .map(|p| p.type_.as_str())
+            .collect::<Vec<_>>()
+            .join(",")
+    );
+    keccak256(signature.as_bytes())
+}
+
+fn keccak256(bytes: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak::v256();
+    hasher.update(bytes);
+    let mut output = [0u8; 32];
+    hasher.finalize(&mut output);
+    output
+}
+
+// ---- ABI type tree ----------------------------------------------------
+
+#[derive(Clone, Debug)]
+enum AbiType {
+    Uint(usize),
+    Int(usize),
+    Bool,
+    Address,
+    FixedBytes(usize),
+    Bytes,
+    Str,
+    Array(Box<AbiType>),
+    FixedArray(Box<AbiType>, usize),
+    Tuple(Vec<AbiType>),
+}
+
+fn parse_type(s: &str) -> Result<AbiType> {
+    let s = s.trim();
+
+    if s.starts_with('(') {
+        let close = find_matching_paren(s)?;
+        let components = split_top_level(&s[1..close]);
+        let fields = components
+            .iter()
+            .map(|c| parse_type(c))
+            .collect::<Result<Vec<_>>>()?;
+        apply_array_suffix(AbiType::Tuple(fields), &s[close + 1..])
+    } else {
+        let (base_str, suffix) = match s.find('[') {
+            Some(i) => (&s[..i], &s[i..]),
+            None => (s, ""),
+        };
+        apply_array_suffix(parse_elementary(base_str)?, suffix)
+    }
+}
+
+fn find_matching_paren(s: &str) -> Result<usize> {
+    let mut depth = 0i32;
+    for (i, c) in s.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Ok(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    bail!("unbalanced parentheses in ABI type string: {}", s)
+}
+
+fn split_top_level(s: &str) -> Vec<String> {
+    if s.is_empty() {
+        return Vec::new();
+    }
+
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut start = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(s[start..i].to_string());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(s[start..].to_string());
+    parts
+}
+
+fn apply_array_suffix(mut base: AbiType, suffix: &str) -> Result<AbiType> {
+    let mut rest = suffix;
+    while !rest.is_empty() {
+        if !rest.starts_with('[') {
+            bail!("malformed array suffix in ABI type string: {}", suffix);
+        }
+        let close = rest
+            .find(']')
+            .ok_or_else(|| eyre::eyre!("unterminated array suffix in ABI type string: {}", suffix))?;
+        let len_str = &rest[1..close];
+
+        base = if len_str.is_empty() {
+            AbiType::Array(Box::new(base))
+        } else {
+            let n: usize = len_str
+                .parse()
+                .map_err(|_| eyre::eyre!("invalid fixed array length: {}", len_str))?;
+            AbiType::FixedArray(Box::new(base), n)
+        };
+
+        rest = &rest[close + 1..];
+    }
+    Ok(base)
+}
+
+fn parse_elementary(s: &str) -> Result<AbiType> {
+    Ok(match s {
+        "uint" => AbiType::Uint(256),
+        "int" => AbiType::Int(256),
+        "bool" => AbiType::Bool,
+        "address" => AbiType::Address,
+        "bytes" => AbiType::Bytes,
+        "string" => AbiType::Str,
+        _ if s.starts_with("uint") => AbiType::Uint(
+            s[4..]
+                .parse()
+                .map_err(|_| eyre::eyre!("invalid uint width: {}", s))?,
+        ),
+        _ if s.starts_with("int") => AbiType::Int(
+            s[3..]
+                .parse()
+                .map_err(|_| eyre::eyre!("invalid int width: {}", s))?,
+        ),
+        _ if s.starts_with("bytes") => AbiType::FixedBytes(
+            s[5..]
+                .parse()
+                .map_err(|_| eyre::eyre!("invalid bytesN width: {}", s))?,
+        ),
+        _ => bail!("unsupported ABI type: {}", s),
+    })
+}
+
+fn is_dynamic(t: &AbiType) -> bool {
+    match t {
+        AbiType::Bytes | AbiType::Str | AbiType::Array(_) => true,
+        AbiType::FixedArray(inner, _) => is_dynamic(inner),
+        AbiType::Tuple(fields) => fields.iter().any(is_dynamic),
+        _ => false,
+    }
+}
+
+// ---- head/tail decoding -------------------------------------------------
+
+fn decode_head_tail(types: &[AbiType], data: &[u8], base: usize) -> Result<Vec<Value>> {
+    let mut head_cursor = base;
+    let mut values = Vec::with_capacity(types.len());
+
+    for t in types {
+        if is_dynamic(t) {
+            let offset = read_usize(data, head_cursor)?;
+            let tail_pos = base
+                .checked_add(offset)
+                .ok_or_else(|| eyre::eyre!("tail offset overflow"))?;
+            values.push(decode_dynamic(t, data, tail_pos)?);
+            head_cursor += 32;
+        } else {
+            let (value, consumed) = decode_static(t, data, head_cursor)?;
+            values.push(value);
+            head_cursor += consumed;
+        }
+    }
+
+    Ok(values)
+}
+
+fn decode_static(t: &AbiType, data: &[u8], at: usize) -> Result<(Value, usize)> {
+    match t {
+        AbiType::Uint(_) => Ok((Value::String(decode_uint_word(read_word(data, at)?)), 32)),
+        AbiType::Int(_) => Ok((Value::String(decode_int_word(read_word(data, at)?)), 32)),
+        AbiType::Bool => Ok((Value::Bool(read_word(data, at)?[31] != 0), 32)),
+        AbiType::Address => {
+            let word = read_word(data, at)?;
+            Ok((Value::String(checksum_address(&word[12..32])), 32))
+        }
+        AbiType::FixedBytes(n) => {
+            let word = read_word(data, at)?;
+            Ok((Value::String(format!("0x{}", hex::encode(&word[..*n]))), 32))
+        }
+        AbiType::Tuple(fields) => {
+            let mut cursor = at;
+            let mut values = Vec::with_capacity(fields.len());
+            for field in fields {
+                let (value, consumed) = decode_static(field, data, cursor)?;
+                values.push(value);
+                cursor += consumed;
+            }
+            Ok((Value::Array(values), cursor - at))
+        }
+        AbiType::FixedArray(inner, n) => {
+            let mut cursor = at;
+            let mut values = Vec::with_capacity(*n);
+            for _ in 0..*n {
+                let (value, consumed) = decode_static(inner, data, cursor)?;
+                values.push(value);
+                cursor += consumed;
+            }
+            Ok((Value::Array(values), cursor - at))
+        }
+        AbiType::Bytes | AbiType::Str | AbiType::Array(_) => {
+            bail!("internal error: dynamic type reached decode_static")
+        }
+    }
+}
+
+fn decode_dynamic(t: &AbiType, data: &[u8], at: usize) -> Result<Value> {
+    match t {
+        AbiType::Bytes => {
+            let len = read_usize(data, at)?;
+            let data_start = at.checked_add(32).ok_or_else(|| eyre::eyre!("bytes data offset overflow"))?;
+            let bytes = read_bytes(data, data_start, len)?;
+            Ok(Value::String(format!("0x{}", hex::encode(bytes))))
+        }
+        AbiType::Str => {
+            let len = read_usize(data, at)?;
+            let data_start = at.checked_add(32).ok_or_else(|| eyre::eyre!("string data offset overflow"))?;
+            let bytes = read_bytes(data, data_start, len)?;
+            Ok(Value::String(String::from_utf8_lossy(bytes).into_owned()))
+        }
+        AbiType::Array(inner) => {
+            let len = read_usize(data, at)?;
+            let elements_start = at.checked_add(32).ok_or_else(|| eyre::eyre!("array data offset overflow"))?;
+            check_array_len(data, elements_start, len)?;
+            let types: Vec<AbiType> = std::iter::repeat((**inner).clone()).take(len).collect();
+            Ok(Value::Array(decode_head_tail(&types, data, elements_start)?))
+        }
+        AbiType::FixedArray(inner, n) => {
+            let types: Vec<AbiType> = std::iter::repeat((**inner).clone()).take(*n).collect();
+            Ok(Value::Array(decode_head_tail(&types, data, at)?))
+        }
+        AbiType::Tuple(fields) => Ok(Value::Array(decode_head_tail(fields, data, at)?)),
+        _ => bail!("internal error: static type reached decode_dynamic"),
+    }
+}
+
+fn read_word<'a>(data: &'a [u8], at: usize) -> Result<&'a [u8]> {
+    let end = at
+        .checked_add(32)
+        .ok_or_else(|| eyre::eyre!("word offset overflow"))?;
+    data.get(at..end)
+        .ok_or_else(|| eyre::eyre!("truncated calldata: word at {} out of bounds ({} bytes)", at, data.len()))
+}
+
+fn read_usize(data: &[u8], at: usize) -> Result<usize> {
+    let word = read_word(data, at)?;
+    if word[..24].iter().any(|&b| b != 0) {
+        bail!("offset/length word at {} exceeds usize range", at);
+    }
+    let mut buf = [0u8; 8];
+    buf.copy_from_slice(&word[24..32]);
+    Ok(u64::from_be_bytes(buf) as usize)
+}
+
+fn read_bytes<'a>(data: &'a [u8], start: usize, len: usize) -> Result<&'a [u8]> {
+    let end = start
+        .checked_add(len)
+        .ok_or_else(|| eyre::eyre!("byte range overflow"))?;
+    data.get(start..end)
+        .ok_or_else(|| eyre::eyre!("truncated calldata: {} bytes at {} out of bounds ({} bytes)", len, start, data.len()))
+}
+
+// A dynamic array's element count comes straight from attacker-controlled
+// calldata, so it must be bounds-checked before it's used to size a `Vec` -
+// an unvalidated `len` (e.g. from a crafted offset/length word) can make the
+// allocator abort the whole process, which isn't even catchable like a
+// panic. Every element takes at least one 32-byte word, so this is a cheap
+// lower bound on how much data the array could possibly need, mirroring
+// what `read_bytes` already enforces for `bytes`/`string`.
+fn check_array_len(data: &[u8], elements_start: usize, len: usize) -> Result<()> {
+    let min_bytes = len.checked_mul(32).ok_or_else(|| eyre::eyre!("array length overflow"))?;
+    let end = elements_start
+        .checked_add(min_bytes)
+        .ok_or_else(|| eyre::eyre!("array bounds overflow"))?;
+    if end > data.len() {
+        bail!(
+            "truncated calldata: array of {} elements at {} needs at least {} bytes, have {}",
+            len,
+            elements_start,
+            min_bytes,
+            data.len()
+        );
+    }
+    Ok(())
+}
+
+fn checksum_address(addr: &[u8]) -> String {
+    let hex_addr = hex::encode(addr);
+    let hash = keccak256(hex_addr.as_bytes());
+
+    let mut result = String::with_capacity(42);
+    result.push_str("0x");
+    for (i, c) in hex_addr.chars().enumerate() {
+        if c.is_ascii_digit() {
+            result.push(c);
+            continue;
+        }
+        let nibble = if i % 2 == 0 {
+            hash[i / 2] >> 4
+        } else {
+            hash[i / 2] & 0x0f
+        };
+        if nibble >= 8 {
+            result.push(c.to_ascii_uppercase());
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+fn word_to_decimal(word: &[u8]) -> String {
+    if word.iter().all(|&b| b == 0) {
+        return "0".to_string();
+    }
+
+    let mut digits = word.to_vec();
+    let mut out = Vec::new();
+    loop {
+        let mut remainder: u32 = 0;
+        let mut all_zero = true;
+        for byte in digits.iter_mut() {
+            let acc = (remainder << 8) | (*byte as u32);
+            *byte = (acc / 10) as u8;
+            remainder = acc % 10;
+            if *byte != 0 {
+                all_zero = false;
+            }
+        }
+        out.push(b'0' + remainder as u8);
+        if all_zero {
+            break;
+        }
+    }
+    out.reverse();
+    String::from_utf8(out).unwrap()
+}
+
+fn decode_uint_word(word: &[u8]) -> String {
+    word_to_decimal(word)
+}
+
+fn decode_int_word(word: &[u8]) -> String {
+    if word[0] & 0x80 == 0 {
+        return word_to_decimal(word);
+    }
+
+    let mut twos_complement = [0u8; 32];
+    let mut carry = 1u16;
+    for i in (0..32).rev() {
+        let sum = (!word[i]) as u16 + carry;
+        twos_complement[i] = sum as u8;
+        carry = sum >> 8;
+    }
+    format!("-{}", word_to_decimal(&twos_complement))
+}
@@ -0,0 +1,363 @@
+// Decompiles contracts straight off a live Ethereum JSON-RPC node over a
+// block range, instead of requiring a pre-exported parquet dataset. Walks
+// each block, finds contract-creation transactions, fetches the resulting
+// deployed bytecode, and feeds it through the existing `ContractProcessor`
+// pipeline - mirroring `RpcSource`'s raw JSON-RPC style in
+// `contract_source.rs`, but driven block-by-block instead of from a fixed
+// address list, and checkpointed so an interrupted run resumes instead of
+// re-decompiling already-cached contracts.
+
+use crate::cache::AbiCache;
+use crate::chain::ChainSpec;
+use crate::processor::{ContractProcessor, ProcessResult};
+use crate::stats::Stats;
+use crate::storage_pool::StorageWorkerPool;
+use crossbeam_channel::{bounded, Receiver, Sender};
+use eyre::{bail, Result};
+use rayon::prelude::*;
+use serde_json::{json, Value};
+use std::collections::BTreeSet;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use tokio::runtime::Runtime;
+use tracing::{debug, warn};
+
+const RESULT_CHANNEL_BUFFER: usize = 10000;
+const CURSOR_FILE_NAME: &str = ".heimdall_ingest_cursor";
+
+/// Tunables for a single `Ingester::run`.
+pub struct IngestConfig {
+    pub rpc_url: String,
+    pub from_block: u64,
+    pub to_block: u64,
+    pub workers: usize,
+    pub timeout_secs: u64,
+    pub skip_resolving: bool,
+    pub extract_storage: bool,
+    pub chain: ChainSpec,
+    /// How many blocks past the committed cursor may be in flight at once.
+    /// Bounds how much progress a crash can lose, since the cursor only
+    /// advances past a block once every contract it created has finished
+    /// decompiling.
+    pub max_blocks_ahead: usize,
+    /// Size of the long-lived storage-extraction worker pool shared by
+    /// every contract this `Ingester` processes.
+    pub storage_pool_size: usize,
+}
+
+/// Tracks ingestion progress so an interrupted run resumes from the next
+/// unprocessed block instead of re-walking the whole range. Mirrors
+/// `ProcessingManifest`'s append-only sidecar design, but persists a single
+/// monotonically advancing block number: blocks only ever complete
+/// out-of-order in memory (workers race ahead up to `max_blocks_ahead`),
+/// never on disk, since the sidecar only records a block once every block
+/// below it has also been recorded.
+struct IngestCursor {
+    file: File,
+    /// Highest block number for which it and everything below it is
+    /// recorded complete on disk.
+    last_committed: Option<u64>,
+    /// First block of this run, which the cursor starts from before any
+    /// block has been committed.
+    first_block: u64,
+    /// Block numbers that finished decompiling but are still waiting on a
+    /// lower block to commit first.
+    pending: BTreeSet<u64>,
+}
+
+impl IngestCursor {
+    fn load(checkpoint_dir: &Path) -> Result<Self> {
+        let cursor_path = checkpoint_dir.join(CURSOR_FILE_NAME);
+        let mut last_committed = None;
+
+        if cursor_path.exists() {
+            let reader = BufReader::new(File::open(&cursor_path)?);
+            for line in reader.lines() {
+                if let Ok(block) = line?.trim().parse::<u64>() {
+                    last_committed = Some(block);
+                }
+            }
+        }
+
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&cursor_path)?;
+
+        Ok(IngestCursor {
+            file,
+            last_committed,
+            first_block: 0,
+            pending: BTreeSet::new(),
+        })
+    }
+
+    /// The first block `Ingester::run` should process.
+    fn resume_from(&self, from_block: u64) -> u64 {
+        match self.last_committed {
+            Some(last) => from_block.max(last + 1),
+            None => from_block,
+        }
+    }
+
+    /// Records that every contract created in `block` finished decompiling.
+    /// Only actually advances the on-disk cursor once `block` and every
+    /// block below it (back to the last committed one) are accounted for,
+    /// so the persisted value always stays contiguous.
+    fn mark_block_done(&mut self, block: u64) -> Result<()> {
+        self.pending.insert(block);
+
+        let mut next = self.last_committed.map(|b| b + 1).unwrap_or(self.first_block);
+        let mut advanced_to = None;
+        while self.pending.remove(&next) {
+            advanced_to = Some(next);
+            next += 1;
+        }
+
+        if let Some(block) = advanced_to {
+            writeln!(self.file, "{}", block)?;
+            self.file.flush()?;
+            self.last_committed = Some(block);
+        }
+
+        Ok(())
+    }
+}
+
+/// Gates how many blocks past the committed cursor may be decompiling at
+/// once, blocking new blocks from starting once `max_ahead` are in flight.
+struct BlockGate {
+    max_ahead: u64,
+    cursor: Mutex<IngestCursor>,
+    cv: Condvar,
+}
+
+impl BlockGate {
+    fn new(cursor: IngestCursor, max_ahead: usize) -> Self {
+        BlockGate {
+            max_ahead: max_ahead.max(1) as u64,
+            cursor: Mutex::new(cursor),
+            cv: Condvar::new(),
+        }
+    }
+
+    fn resume_from(&self, from_block: u64) -> u64 {
+        let mut cursor = self.cursor.lock().unwrap();
+        let start = cursor.resume_from(from_block);
+        cursor.first_block = start;
+        start
+    }
+
+    /// Blocks the calling (worker) thread until fewer than `max_ahead`
+    /// blocks are outstanding ahead of the committed cursor.
+    fn wait_for_slot(&self, block: u64) {
+        let mut cursor = self.cursor.lock().unwrap();
+        loop {
+            let next = cursor.last_committed.map(|b| b + 1).unwrap_or(cursor.first_block);
+            if block < next.saturating_add(self.max_ahead) {
+                return;
+            }
+            cursor = self.cv.wait(cursor).unwrap();
+        }
+    }
+
+    fn mark_block_done(&self, block: u64) -> Result<()> {
+        let mut cursor = self.cursor.lock().unwrap();
+        cursor.mark_block_done(block)?;
+        self.cv.notify_all();
+        Ok(())
+    }
+}
+
+/// Walks a block range over a live JSON-RPC node, detects contract
+/// creations, and decompiles the deployed bytecode through the same
+/// `ContractProcessor`/`AbiCache` pipeline `StreamProcessor` uses for
+/// parquet exports.
+pub struct Ingester {
+    cache: Arc<AbiCache>,
+    stats: Arc<Stats>,
+    config: IngestConfig,
+    client: reqwest::blocking::Client,
+}
+
+impl Ingester {
+    pub fn new(cache: Arc<AbiCache>, stats: Arc<Stats>, config: IngestConfig) -> Self {
+        Ingester {
+            cache,
+            stats,
+            config,
+            client: reqwest::blocking::Client::new(),
+        }
+    }
+
+    /// Starts ingestion on a background thread and returns a receiver that
+    /// yields a `ProcessResult` for every contract as soon as it's
+    /// decompiled. Dropping the receiver stops ingestion early.
+    /// `checkpoint_dir` holds the ingestion cursor sidecar; pass the same
+    /// directory across restarts to resume.
+    pub fn run(self, checkpoint_dir: &Path) -> Result<Receiver<ProcessResult>> {
+        let cursor = IngestCursor::load(checkpoint_dir)?;
+        let (tx, rx) = bounded(RESULT_CHANNEL_BUFFER);
+
+        thread::spawn(move || {
+            if let Err(e) = self.run_inner(cursor, tx) {
+                warn!("Ingestion aborted: {}", e);
+            }
+        });
+
+        Ok(rx)
+    }
+
+    fn run_inner(&self, cursor: IngestCursor, result_tx: Sender<ProcessResult>) -> Result<()> {
+        let gate = Arc::new(BlockGate::new(cursor, self.config.max_blocks_ahead));
+        let start_block = gate.resume_from(self.config.from_block);
+
+        if start_block > self.config.to_block {
+            debug!("Ingestion range already fully committed, nothing to do");
+            return Ok(());
+        }
+
+        let storage_pool = Arc::new(StorageWorkerPool::new(self.config.storage_pool_size));
+        let processor = Arc::new(ContractProcessor::new(
+            self.cache.clone(),
+            self.config.timeout_secs,
+            self.config.skip_resolving,
+            self.config.extract_storage,
+            self.config.chain,
+            storage_pool,
+        ));
+
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(self.config.workers.max(1))
+            .thread_name(|i| format!("ingest-worker-{}", i))
+            .build()?
+            .install(|| -> Result<()> {
+                (start_block..=self.config.to_block)
+                    .into_par_iter()
+                    .try_for_each(|block_number| -> Result<()> {
+                        thread_local! {
+                            static RUNTIME: Runtime =
+                                Runtime::new().expect("Failed to create runtime");
+                        }
+
+                        gate.wait_for_slot(block_number);
+
+                        // A transient RPC blip on one block shouldn't abort
+                        // `try_for_each` for the whole range - log it and move
+                        // on, the same way `subscribe.rs` skips a bad head
+                        // instead of tearing down the subscription.
+                        let creations = match self.fetch_contract_creations(block_number) {
+                            Ok(creations) => creations,
+                            Err(e) => {
+                                warn!(
+                                    "Failed to fetch contract creations for block {}: {}",
+                                    block_number, e
+                                );
+                                return gate.mark_block_done(block_number);
+                            }
+                        };
+
+                        for (address, code) in creations {
+                            let result = match RUNTIME.with(|rt| {
+                                rt.block_on(processor.process_contract(address, code))
+                            }) {
+                                Ok(result) => result,
+                                Err(e) => {
+                                    warn!(
+                                        "Failed to process a contract in block {}: {}",
+                                        block_number, e
+                                    );
+                                    continue;
+                                }
+                            };
+
+                            self.stats.record_result(
+                                result.cached,
+                                result.success,
+                                result
+                                    .error
+                                    .as_deref()
+                                    .is_some_and(|e| e.contains("timed out")),
+                                result.durations,
+                            );
+
+                            if result_tx.send(result).is_err() {
+                                debug!("Result receiver dropped, stopping ingestion");
+                                return Ok(());
+                            }
+                        }
+
+                        gate.mark_block_done(block_number)
+                    })
+            })
+    }
+
+    /// Finds every contract deployed in `block_number` and fetches its
+    /// deployed bytecode. `eth_getBlockByNumber` with full transaction
+    /// objects identifies creation transactions (`to` is null);
+    /// `eth_getTransactionReceipt` recovers the resulting `contractAddress`
+    /// for each.
+    fn fetch_contract_creations(&self, block_number: u64) -> Result<Vec<(String, String)>> {
+        let block: Value = self
+            .rpc_call("eth_getBlockByNumber", json!([format!("0x{:x}", block_number), true]))?;
+
+        let transactions = match block.get("transactions").and_then(|t| t.as_array()) {
+            Some(txs) => txs,
+            None => bail!("Block {} has no transactions field", block_number),
+        };
+
+        let creation_hashes: Vec<&str> = transactions
+            .iter()
+            .filter(|tx| tx.get("to").map(|to| to.is_null()).unwrap_or(true))
+            .filter_map(|tx| tx.get("hash").and_then(|h| h.as_str()))
+            .collect();
+
+        if creation_hashes.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut creations = Vec::with_capacity(creation_hashes.len());
+        for hash in creation_hashes {
+            let receipt: Value = self.rpc_call("eth_getTransactionReceipt", json!([hash]))?;
+            let address = match receipt.get("contractAddress").and_then(|a| a.as_str()) {
+                Some(address) => address.to_string(),
+                None => {
+                    debug!("Creation tx {} has no contractAddress in its receipt", hash);
+                    continue;
+                }
+            };
+
+            let code: String = self.rpc_call("eth_getCode", json!([address, "latest"]))?;
+            if code.is_empty() || code == "0x" {
+                continue;
+            }
+
+            creations.push((address, code));
+        }
+
+        Ok(creations)
+    }
+
+    fn rpc_call<T: serde::de::DeserializeOwned>(&self, method: &str, params: Value) -> Result<T> {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+
+        let response: Value = self.client.post(&self.config.rpc_url).json(&request).send()?.json()?;
+
+        if let Some(error) = response.get("error") {
+            bail!("{} failed: {:?}", method, error);
+        }
+
+        let result = response
+            .get("result")
+            .ok_or_else(|| eyre::eyre!("{} response missing result", method))?;
+        Ok(serde_json::from_value(result.clone())?)
+    }
+}
@@ -1,16 +1,25 @@
+mod bloom;
 mod cache;
+mod chain;
+mod contract_source;
+mod decoder;
+mod ingest;
+mod manifest;
 mod parquet_reader;
 mod processor;
 mod stats;
+mod storage_pool;
 mod stream_processor;
+mod subscribe;
 mod types;
 
 use cache::AbiCache;
+use chain::ChainSpec;
 use clap::Parser;
 use colored::Colorize;
 use eyre::Result;
 use parquet_reader::ParquetReader;
-use processor::ABANDONED_THREADS;
+use storage_pool::STUCK_WORKERS_PEAK;
 use stats::Stats;
 use std::path::PathBuf;
 use std::sync::atomic::Ordering;
@@ -33,6 +42,20 @@ struct Args {
     #[clap(short = 'w', long)]
     workers: Option<usize>,
 
+    /// Number of parquet reader threads (sized independently of `workers`)
+    #[clap(short = 'r', long, default_value = "4")]
+    reader_threads: usize,
+
+    /// Resume from a previous interrupted run, skipping parquet files
+    /// already recorded as complete in the parquet directory's manifest
+    #[clap(long)]
+    resume: bool,
+
+    /// Restrict discovery to parquet filenames matching this glob pattern
+    /// (`*`/`?` wildcards), useful when pointed at a partitioned dataset root
+    #[clap(long)]
+    pattern: Option<String>,
+
     /// Decompilation timeout in seconds
     #[clap(short = 't', long, default_value = "25")]
     timeout: u64,
@@ -45,6 +68,14 @@ struct Args {
     #[clap(short = 'e', long, default_value = "true")]
     extract_storage: bool,
 
+    /// EVM fork to analyze storage layouts against (e.g. "shanghai", "cancun")
+    #[clap(long)]
+    chain: Option<String>,
+
+    /// Number of long-lived storage-extraction worker threads
+    #[clap(long, default_value_t = storage_pool::DEFAULT_STORAGE_WORKER_POOL_SIZE)]
+    storage_pool_size: usize,
+
     /// Update interval for progress display in milliseconds
     #[clap(short = 'u', long, default_value = "500")]
     update_interval: u64,
@@ -58,11 +89,29 @@ struct Args {
     #[clap(long)]
     debug_cache: bool,
 
+    /// Decode calldata (hex, with or without 0x prefix) against the cached
+    /// ABI for --decode-bytecode instead of processing parquet files
+    #[clap(long)]
+    decode_calldata: Option<String>,
+
+    /// Contract bytecode (hex) whose cached ABI to decode
+    /// --decode-calldata against
+    #[clap(long)]
+    decode_bytecode: Option<String>,
+
     /// Clear cache before processing
     #[clap(long)]
     clear_cache: bool,
 }
 
+fn parse_chain(s: &str) -> Result<ChainSpec> {
+    match s.to_ascii_lowercase().as_str() {
+        "shanghai" => Ok(ChainSpec::shanghai()),
+        "cancun" => Ok(ChainSpec::cancun()),
+        other => Err(eyre::eyre!("Unknown chain/fork: {}", other)),
+    }
+}
+
 fn main() -> Result<()> {
     let args = Args::parse();
 
@@ -114,6 +163,24 @@ fn main() -> Result<()> {
         return Ok(());
     }
 
+    // Decode mode - decode calldata against a cached contract's ABI
+    if let Some(calldata_hex) = &args.decode_calldata {
+        let bytecode = args
+            .decode_bytecode
+            .as_ref()
+            .ok_or_else(|| eyre::eyre!("--decode-calldata requires --decode-bytecode"))?;
+
+        let cache = Arc::new(AbiCache::new(args.cache_dir.clone())?);
+        let abi = cache
+            .get(bytecode, args.skip_resolving)
+            .ok_or_else(|| eyre::eyre!("no cached ABI for the given bytecode; run the cache builder over it first"))?;
+
+        let calldata = hex::decode(calldata_hex.strip_prefix("0x").unwrap_or(calldata_hex))?;
+        let decoded = decoder::decode_calldata(&abi, &calldata)?;
+        println!("{}", serde_json::to_string_pretty(&decoded.as_json())?);
+        return Ok(());
+    }
+
     // Initialize cache
     let cache = Arc::new(AbiCache::new(args.cache_dir.clone())?);
     println!(
@@ -160,7 +227,18 @@ fn main() -> Result<()> {
         args.timeout,
         args.skip_resolving,
         args.extract_storage,
-    );
+    )
+    .with_reader_threads(args.reader_threads)
+    .with_resume(args.resume)
+    .with_storage_pool_size(args.storage_pool_size);
+    let processor = match args.pattern {
+        Some(pattern) => processor.with_pattern(pattern),
+        None => processor,
+    };
+    let processor = match args.chain {
+        Some(chain) => processor.with_chain(parse_chain(&chain)?),
+        None => processor,
+    };
 
     let start = Instant::now();
     processor.process_all_parquets(&args.parquet_dir)?;
@@ -174,14 +252,14 @@ fn main() -> Result<()> {
     println!("{}", "Cache Statistics:".bright_cyan());
     println!("{}", cache.get_stats_summary());
 
-    // Check for abandoned threads
-    let abandoned = ABANDONED_THREADS.load(Ordering::Relaxed);
-    if abandoned > 0 {
+    // Check how much the storage-extraction worker pool was ever backed up
+    let stuck_peak = STUCK_WORKERS_PEAK.load(Ordering::Relaxed);
+    if stuck_peak > 0 {
         println!(
             "\n{}",
             format!(
-                "WARNING: {} threads were abandoned due to timeouts",
-                abandoned
+                "WARNING: up to {} storage-extraction workers were stuck past their deadline at once",
+                stuck_peak
             )
             .yellow()
         );
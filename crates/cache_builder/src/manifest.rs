@@ -0,0 +1,68 @@
+// Tracks which parquet files have been fully processed across restarts, so
+// a multi-hour run over thousands of files can resume after a crash
+// instead of redoing completed work - mirroring how bulk blockchain
+// indexers persist which inputs they've already consumed.
+
+use eyre::Result;
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+const MANIFEST_FILE_NAME: &str = ".heimdall_manifest";
+
+pub struct ProcessingManifest {
+    file: File,
+    completed: HashMap<PathBuf, String>,
+}
+
+impl ProcessingManifest {
+    /// Loads the manifest sidecar from `parquet_dir` (creating it if
+    /// missing) and keeps it open for further appends.
+    pub fn load(parquet_dir: &Path) -> Result<Self> {
+        let manifest_path = parquet_dir.join(MANIFEST_FILE_NAME);
+        let mut completed = HashMap::new();
+
+        if manifest_path.exists() {
+            let reader = BufReader::new(File::open(&manifest_path)?);
+            for line in reader.lines() {
+                let line = line?;
+                if let Some((path, hash)) = line.split_once('\t') {
+                    completed.insert(PathBuf::from(path), hash.to_string());
+                }
+            }
+        }
+
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&manifest_path)?;
+
+        Ok(ProcessingManifest { file, completed })
+    }
+
+    /// True if `path` was already recorded as fully processed with the same
+    /// content hash it has now. A changed hash means the file was
+    /// overwritten since the last run, so it's treated as not yet done.
+    pub fn is_complete(&self, path: &Path, content_hash: &str) -> bool {
+        self.completed.get(path).is_some_and(|h| h == content_hash)
+    }
+
+    /// Atomically appends `path` to the manifest, flushing immediately so a
+    /// crash mid-run loses at most the file that was in flight, never one
+    /// already recorded as complete.
+    pub fn mark_complete(&mut self, path: &Path, content_hash: &str) -> Result<()> {
+        writeln!(self.file, "{}\t{}", path.display(), content_hash)?;
+        self.file.flush()?;
+        self.completed
+            .insert(path.to_path_buf(), content_hash.to_string());
+        Ok(())
+    }
+}
+
+/// Content hash used to detect a parquet file that changed since the last
+/// run despite keeping the same path.
+pub fn hash_file(path: &Path) -> Result<String> {
+    let bytes = std::fs::read(path)?;
+    Ok(blake3::hash(&bytes).to_hex().to_string())
+}
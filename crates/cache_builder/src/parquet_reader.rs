@@ -3,7 +3,7 @@ use arrow::record_batch::RecordBatch;
 use eyre::Result;
 use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
 use std::fs::File;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use tracing::{debug, info};
 
 #[derive(Debug, Clone)]
@@ -134,24 +134,14 @@ impl ParquetReader {
         Ok(contracts)
     }
 
-    pub fn read_all_parquets(directory: &Path) -> Result<Vec<Contract>> {
+    /// Loads every parquet file under `directory`, recursing into
+    /// subdirectories so partitioned layouts (e.g.
+    /// `chain=.../block_range=.../*.parquet`) are fully discovered. An
+    /// optional glob `pattern` further restricts which filenames are read.
+    pub fn read_all_parquets(directory: &Path, pattern: Option<&str>) -> Result<Vec<Contract>> {
         let mut all_contracts = Vec::new();
 
-        // Find all parquet files
-        let entries = std::fs::read_dir(directory)?;
-        let mut parquet_files: Vec<_> = entries
-            .filter_map(|entry| entry.ok())
-            .filter(|entry| {
-                entry
-                    .path()
-                    .extension()
-                    .map_or(false, |ext| ext == "parquet")
-            })
-            .map(|entry| entry.path())
-            .collect();
-
-        // Sort for consistent ordering
-        parquet_files.sort();
+        let parquet_files = discover_parquet_files(directory, pattern)?;
 
         info!("Found {} parquet files", parquet_files.len());
 
@@ -176,4 +166,80 @@ impl ParquetReader {
         info!("Total contracts loaded: {}", all_contracts.len());
         Ok(all_contracts)
     }
+}
+
+/// Recursively walks `root` collecting every `*.parquet` file, so
+/// partitioned dataset layouts (e.g. `chain=.../block_range=.../*.parquet`)
+/// are fully discovered from their root directory. An optional glob
+/// `pattern` (supporting `*` and `?` wildcards, matched against the
+/// filename only) further restricts which files are returned. Shared by
+/// `read_all_parquets` and `StreamProcessor::find_parquet_files` so both
+/// walk a dataset the same way. Results are sorted for deterministic
+/// ordering.
+pub fn discover_parquet_files(root: &Path, pattern: Option<&str>) -> Result<Vec<PathBuf>> {
+    if !root.exists() {
+        return Err(eyre::eyre!("Directory does not exist: {:?}", root));
+    }
+
+    let mut files = Vec::new();
+    let mut dirs = vec![root.to_path_buf()];
+
+    while let Some(dir) = dirs.pop() {
+        for entry in std::fs::read_dir(&dir)? {
+            let entry = entry?;
+            let path = entry.path();
+
+            if path.is_dir() {
+                dirs.push(path);
+                continue;
+            }
+
+            if path.extension().and_then(|ext| ext.to_str()) != Some("parquet") {
+                continue;
+            }
+
+            if let Some(pattern) = pattern {
+                let name_matches = path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| glob_match(pattern, name));
+                if !name_matches {
+                    continue;
+                }
+            }
+
+            files.push(path);
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+// Minimal glob matcher supporting `*` (any run of characters, including
+// none) and `?` (exactly one character) - enough for filename filters
+// like `"ethereum__contracts__148*"` without pulling in a dedicated glob crate.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let mut dp = vec![vec![false; text.len() + 1]; pattern.len() + 1];
+    dp[0][0] = true;
+    for (i, &p) in pattern.iter().enumerate() {
+        if p == '*' {
+            dp[i + 1][0] = dp[i][0];
+        }
+    }
+
+    for (i, &p) in pattern.iter().enumerate() {
+        for j in 0..text.len() {
+            dp[i + 1][j + 1] = match p {
+                '*' => dp[i][j + 1] || dp[i + 1][j],
+                '?' => dp[i][j],
+                c => c == text[j] && dp[i][j],
+            };
+        }
+    }
+
+    dp[pattern.len()][text.len()]
 }
\ No newline at end of file
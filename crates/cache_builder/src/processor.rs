@@ -1,4 +1,7 @@
 use crate::cache::AbiCache;
+use crate::chain::ChainSpec;
+use crate::stats::Durations;
+use crate::storage_pool::StorageWorkerPool;
 use crate::types::{
     convert_event_param, convert_function, convert_param, state_mutability_to_string, ABI,
     ABIError, ABIEvent, ABIFunction, ABIParam, StorageSlot,
@@ -7,22 +10,17 @@ use alloy_json_abi::StateMutability;
 use eyre::Result;
 use heimdall_decompiler::{decompile, DecompilerArgsBuilder};
 use indexmap::IndexMap;
-use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
-use std::thread;
 use std::time::{Duration, Instant};
-use storage_layout_extractor::{self as sle, extractor::{chain::{version::EthereumVersion, Chain}, contract::Contract}};
-use tokio::runtime::Runtime;
-use tracing::{debug, warn};
-
-// Track abandoned threads globally
-pub static ABANDONED_THREADS: AtomicUsize = AtomicUsize::new(0);
+use tracing::warn;
 
 pub struct ContractProcessor {
     cache: Arc<AbiCache>,
     timeout_secs: u64,
     skip_resolving: bool,
     extract_storage: bool,
+    chain: ChainSpec,
+    storage_pool: Arc<StorageWorkerPool>,
 }
 
 impl ContractProcessor {
@@ -31,12 +29,16 @@ impl ContractProcessor {
         timeout_secs: u64,
         skip_resolving: bool,
         extract_storage: bool,
+        chain: ChainSpec,
+        storage_pool: Arc<StorageWorkerPool>,
     ) -> Self {
         ContractProcessor {
             cache,
             timeout_secs,
             skip_resolving,
             extract_storage,
+            chain,
+            storage_pool,
         }
     }
 
@@ -46,22 +48,30 @@ impl ContractProcessor {
         code: String,
     ) -> Result<ProcessResult> {
         let start_time = Instant::now();
+        let mut durations = Durations::default();
 
         // Check cache first
-        if self.cache.exists(&code, self.skip_resolving) {
+        let cache_check_start = Instant::now();
+        let cache_hit = self.cache.exists(&code, self.skip_resolving);
+        durations.cache_io += cache_check_start.elapsed();
+
+        if cache_hit {
             return Ok(ProcessResult {
                 address: contract_address,
                 cached: true,
                 success: true,
                 error: None,
                 duration: start_time.elapsed(),
+                durations,
             });
         }
 
         // Decompile the contract
-        let (abi, decompile_error) = match self.decompile_with_timeout(&code).await {
+        let decompile_start = Instant::now();
+        let (abi, decompile_error) = match self.decompile_with_timeout(&code, &mut durations).await {
             Ok(abi) => (abi, None),
             Err(e) => {
+                durations.decompile += decompile_start.elapsed();
                 let error_msg = format!("{:?}", e);
                 if error_msg.contains("timed out") || error_msg.contains("Execution timed out") {
                     // Create minimal ABI with error
@@ -78,9 +88,11 @@ impl ContractProcessor {
         };
 
         // Write to cache
+        let cache_write_start = Instant::now();
         if let Err(e) = self.cache.put(&code, self.skip_resolving, &abi) {
             warn!("Failed to write to cache: {}", e);
         }
+        durations.cache_io += cache_write_start.elapsed();
 
         Ok(ProcessResult {
             address: contract_address,
@@ -88,10 +100,13 @@ impl ContractProcessor {
             success: decompile_error.is_none(),
             error: decompile_error,
             duration: start_time.elapsed(),
+            durations,
         })
     }
 
-    async fn decompile_with_timeout(&self, code: &str) -> Result<ABI> {
+    /// Decompiles `code` and, if requested, extracts its storage layout,
+    /// accumulating the time each stage took into `durations`.
+    async fn decompile_with_timeout(&self, code: &str, durations: &mut Durations) -> Result<ABI> {
         let timeout_ms = self.timeout_secs.saturating_mul(1000);
 
         let args = DecompilerArgsBuilder::new()
@@ -106,11 +121,13 @@ impl ContractProcessor {
             .build()?;
 
         // Run decompilation with timeout
+        let decompile_stage_start = Instant::now();
         let decompile_result = tokio::time::timeout(
             Duration::from_secs(self.timeout_secs),
             decompile(args),
         )
         .await??;
+        durations.decompile += decompile_stage_start.elapsed();
 
         // Convert to our ABI format
         let json_abi = decompile_result.abi;
@@ -199,7 +216,10 @@ impl ContractProcessor {
 
         // Extract storage if requested
         let (storage_layout, storage_error) = if self.extract_storage {
-            self.extract_storage_with_timeout(code)
+            let storage_stage_start = Instant::now();
+            let result = self.extract_storage_with_timeout(code);
+            durations.storage_extraction += storage_stage_start.elapsed();
+            result
         } else {
             (Vec::new(), None)
         };
@@ -232,108 +252,8 @@ impl ContractProcessor {
             return (Vec::new(), Some("Empty bytecode after decoding".to_string()));
         }
 
-        let contract = Contract::new(
-            bytes,
-            Chain::Ethereum {
-                version: EthereumVersion::Shanghai,
-            },
-        );
-
-        let (tx, rx) = std::sync::mpsc::channel::<Result<Vec<StorageSlot>, String>>();
-        let done = Arc::new(AtomicBool::new(false));
-        let done_clone = done.clone();
-        let timeout_secs = self.timeout_secs;
-
-        let handle = thread::spawn(move || {
-            match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-                let watchdog = sle::watchdog::FlagWatchdog::new(done_clone)
-                    .polling_every(100)
-                    .in_rc();
-
-                let result = sle::new(
-                    contract,
-                    sle::vm::Config::default(),
-                    sle::tc::Config::default(),
-                    watchdog,
-                )
-                .analyze();
-
-                match result {
-                    Ok(layout) => {
-                        let slots: Vec<StorageSlot> = layout
-                            .slots()
-                            .iter()
-                            .filter(|slot| {
-                                let typ = slot.typ.to_solidity_type();
-                                typ != "unknown"
-                            })
-                            .map(|slot| slot.clone().into())
-                            .collect();
-                        Ok(slots)
-                    }
-                    Err(e) => {
-                        let error_msg = if format!("{:?}", e).contains("StoppedByWatchdog") {
-                            format!("Storage extraction timed out after {} seconds", timeout_secs)
-                        } else {
-                            format!("Storage extraction failed: {:?}", e)
-                        };
-                        Err(error_msg)
-                    }
-                }
-            })) {
-                Ok(result) => {
-                    let _ = tx.send(result);
-                }
-                Err(panic) => {
-                    let panic_msg = if let Some(s) = panic.downcast_ref::<String>() {
-                        s.clone()
-                    } else if let Some(s) = panic.downcast_ref::<&str>() {
-                        s.to_string()
-                    } else {
-                        "Unknown panic during storage extraction".to_string()
-                    };
-                    let _ = tx.send(Err(format!("Storage extraction panicked: {}", panic_msg)));
-                }
-            }
-        });
-
-        match rx.recv_timeout(Duration::from_secs(self.timeout_secs)) {
-            Ok(Ok(slots)) => {
-                done.store(true, Ordering::SeqCst);
-                let _ = handle.join();
-                (slots, None)
-            }
-            Ok(Err(e)) => {
-                done.store(true, Ordering::SeqCst);
-                let _ = handle.join();
-                (Vec::new(), Some(e))
-            }
-            Err(_) => {
-                // Timeout occurred
-                done.store(true, Ordering::SeqCst);
-
-                // Give thread grace period to finish
-                match rx.recv_timeout(Duration::from_millis(100)) {
-                    Ok(Ok(slots)) => {
-                        let _ = handle.join();
-                        (slots, None)
-                    }
-                    Ok(Err(e)) => {
-                        let _ = handle.join();
-                        (Vec::new(), Some(e))
-                    }
-                    _ => {
-                        // Thread unresponsive - abandon it
-                        std::mem::drop(handle);
-                        ABANDONED_THREADS.fetch_add(1, Ordering::Relaxed);
-                        (
-                            Vec::new(),
-                            Some(format!("Storage extraction timed out after {} seconds", self.timeout_secs)),
-                        )
-                    }
-                }
-            }
-        }
+        self.storage_pool
+            .extract(bytes, self.chain, Duration::from_secs(self.timeout_secs))
     }
 }
 
@@ -343,4 +263,5 @@ pub struct ProcessResult {
     pub success: bool,
     pub error: Option<String>,
     pub duration: Duration,
+    pub durations: Durations,
 }
\ No newline at end of file
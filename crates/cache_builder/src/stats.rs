@@ -2,6 +2,76 @@ use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
+const HISTOGRAM_BUCKETS: usize = 40;
+
+/// Lock-free log-scale latency histogram: bucket `i` holds samples whose
+/// microsecond duration falls in `[2^i - 1, 2^(i+1) - 1)`, so ~40 buckets
+/// span from sub-millisecond latencies up to multi-hour outliers without a
+/// fixed linear range. `percentile` approximates a rank by summing bucket
+/// counts until the target is crossed, returning that bucket's upper bound.
+pub struct LatencyHistogram {
+    buckets: [AtomicU64; HISTOGRAM_BUCKETS],
+}
+
+impl LatencyHistogram {
+    pub fn new() -> Self {
+        LatencyHistogram {
+            buckets: std::array::from_fn(|_| AtomicU64::new(0)),
+        }
+    }
+
+    fn bucket_for(micros: u64) -> usize {
+        let bucket = 63 - (micros + 1).leading_zeros() as usize;
+        bucket.min(HISTOGRAM_BUCKETS - 1)
+    }
+
+    pub fn record(&self, duration: Duration) {
+        let micros = duration.as_micros().min(u64::MAX as u128) as u64;
+        self.buckets[Self::bucket_for(micros)].fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Approximates the `p`th percentile (0.0-100.0) of recorded samples.
+    pub fn percentile(&self, p: f64) -> Duration {
+        let counts: Vec<u64> = self
+            .buckets
+            .iter()
+            .map(|b| b.load(Ordering::Relaxed))
+            .collect();
+        let total: u64 = counts.iter().sum();
+        if total == 0 {
+            return Duration::ZERO;
+        }
+
+        let target = (((p / 100.0) * total as f64).ceil() as u64).max(1);
+        let mut cumulative = 0u64;
+        for (i, count) in counts.iter().enumerate() {
+            cumulative += count;
+            if cumulative >= target {
+                let upper_micros = (1u64 << (i + 1)) - 1;
+                return Duration::from_micros(upper_micros);
+            }
+        }
+
+        Duration::from_micros((1u64 << HISTOGRAM_BUCKETS) - 1)
+    }
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Per-phase timing breakdown for a single `process_contract` call, so
+/// `Stats` can tell whether decompilation, storage extraction, or cache I/O
+/// dominates a batch instead of only seeing their sum.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Durations {
+    pub decompile: Duration,
+    pub storage_extraction: Duration,
+    pub cache_io: Duration,
+}
+
 pub struct Stats {
     pub start_time: Instant,
     pub total_contracts: AtomicUsize,
@@ -10,7 +80,12 @@ pub struct Stats {
     pub successes: AtomicUsize,
     pub errors: AtomicUsize,
     pub timeouts: AtomicUsize,
-    pub total_processing_time: AtomicU64, // in microseconds
+    pub decompile_time: AtomicU64, // in microseconds
+    pub storage_time: AtomicU64,   // in microseconds
+    pub cache_io_time: AtomicU64,  // in microseconds
+    pub decompile_latencies: LatencyHistogram,
+    pub storage_latencies: LatencyHistogram,
+    pub cache_io_latencies: LatencyHistogram,
 }
 
 impl Stats {
@@ -23,17 +98,16 @@ impl Stats {
             successes: AtomicUsize::new(0),
             errors: AtomicUsize::new(0),
             timeouts: AtomicUsize::new(0),
-            total_processing_time: AtomicU64::new(0),
+            decompile_time: AtomicU64::new(0),
+            storage_time: AtomicU64::new(0),
+            cache_io_time: AtomicU64::new(0),
+            decompile_latencies: LatencyHistogram::new(),
+            storage_latencies: LatencyHistogram::new(),
+            cache_io_latencies: LatencyHistogram::new(),
         })
     }
 
-    pub fn record_result(
-        &self,
-        cached: bool,
-        success: bool,
-        is_timeout: bool,
-        duration: Duration,
-    ) {
+    pub fn record_result(&self, cached: bool, success: bool, is_timeout: bool, durations: Durations) {
         self.processed.fetch_add(1, Ordering::Relaxed);
 
         if cached {
@@ -47,9 +121,22 @@ impl Stats {
             }
         }
 
-        // Add processing time in microseconds
-        self.total_processing_time
-            .fetch_add(duration.as_micros() as u64, Ordering::Relaxed);
+        self.decompile_time
+            .fetch_add(durations.decompile.as_micros() as u64, Ordering::Relaxed);
+        self.storage_time
+            .fetch_add(durations.storage_extraction.as_micros() as u64, Ordering::Relaxed);
+        self.cache_io_time
+            .fetch_add(durations.cache_io.as_micros() as u64, Ordering::Relaxed);
+
+        self.decompile_latencies.record(durations.decompile);
+        self.storage_latencies.record(durations.storage_extraction);
+        self.cache_io_latencies.record(durations.cache_io);
+    }
+
+    fn total_processing_micros(&self) -> u64 {
+        self.decompile_time.load(Ordering::Relaxed)
+            + self.storage_time.load(Ordering::Relaxed)
+            + self.cache_io_time.load(Ordering::Relaxed)
     }
 
     pub fn get_summary(&self) -> String {
@@ -68,8 +155,7 @@ impl Stats {
         };
 
         let avg_time = if processed > 0 {
-            let total_micros = self.total_processing_time.load(Ordering::Relaxed);
-            Duration::from_micros(total_micros / processed as u64)
+            Duration::from_micros(self.total_processing_micros() / processed as u64)
         } else {
             Duration::ZERO
         };
@@ -132,6 +218,16 @@ impl Stats {
             100.0
         };
 
+        let phase = |name: &str, histogram: &LatencyHistogram| {
+            format!(
+                "  {:<18} p50: {:>7.2}ms  p95: {:>7.2}ms  p99: {:>7.2}ms",
+                name,
+                histogram.percentile(50.0).as_secs_f64() * 1000.0,
+                histogram.percentile(95.0).as_secs_f64() * 1000.0,
+                histogram.percentile(99.0).as_secs_f64() * 1000.0,
+            )
+        };
+
         format!(
             r#"
 === Final Summary ===
@@ -145,6 +241,11 @@ Processed:          {}
 Success rate:       {:.1}%
 Total time:         {:.2}s
 Overall throughput: {:.1} contracts/sec
+
+Latency by phase:
+{}
+{}
+{}
 "#,
             total,
             processed,
@@ -155,7 +256,10 @@ Overall throughput: {:.1} contracts/sec
             errors - timeouts,
             success_rate,
             elapsed.as_secs_f64(),
-            throughput
+            throughput,
+            phase("Decompile", &self.decompile_latencies),
+            phase("Storage extraction", &self.storage_latencies),
+            phase("Cache I/O", &self.cache_io_latencies),
         )
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,178 @@
+// A fixed-size pool of long-lived worker threads that own the
+// `storage_layout_extractor` runtime, replacing the old approach in
+// `ContractProcessor::extract_storage_with_timeout` of spawning a fresh OS
+// thread per contract and abandoning it on timeout. Jobs are submitted
+// over a bounded channel; a worker runs one to completion - or until its
+// `FlagWatchdog` flag is flipped on timeout and `analyze()` cooperatively
+// unwinds - then loops back for the next job instead of being orphaned.
+
+use crate::chain::ChainSpec;
+use crate::types::{convert_storage_slots, StorageSlot};
+use crossbeam_channel::{bounded, Receiver, Sender};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use storage_layout_extractor::{self as sle, extractor::contract::Contract};
+
+pub const DEFAULT_STORAGE_WORKER_POOL_SIZE: usize = 8;
+
+/// How many storage-extraction jobs are currently running past their
+/// caller's deadline, waiting for the worker to notice its watchdog flag
+/// and unwind. A worker count here reflects resource pressure from
+/// pathological bytecode; unlike the old per-contract thread spawning,
+/// this never grows unboundedly since every worker eventually returns to
+/// the pool.
+pub static STUCK_WORKERS: AtomicUsize = AtomicUsize::new(0);
+/// High-water mark of `STUCK_WORKERS` for the lifetime of the process, so
+/// a final summary can report how much pressure a run saw even after
+/// every job has since resolved.
+pub static STUCK_WORKERS_PEAK: AtomicUsize = AtomicUsize::new(0);
+
+struct Job {
+    bytecode: Vec<u8>,
+    chain: ChainSpec,
+    done: Arc<AtomicBool>,
+    respond: std::sync::mpsc::Sender<Result<Vec<StorageSlot>, String>>,
+}
+
+pub struct StorageWorkerPool {
+    jobs: Sender<Job>,
+}
+
+impl StorageWorkerPool {
+    /// Spawns `size` long-lived worker threads (at least one).
+    pub fn new(size: usize) -> Self {
+        let size = size.max(1);
+        let (jobs_tx, jobs_rx) = bounded::<Job>(size * 4);
+
+        for i in 0..size {
+            let jobs_rx = jobs_rx.clone();
+            thread::Builder::new()
+                .name(format!("storage-worker-{}", i))
+                .spawn(move || Self::worker_loop(jobs_rx))
+                .expect("Failed to spawn storage worker thread");
+        }
+
+        StorageWorkerPool { jobs: jobs_tx }
+    }
+
+    fn worker_loop(jobs: Receiver<Job>) {
+        for job in jobs {
+            let result = Self::run_job(&job.bytecode, job.chain, &job.done);
+            let _ = job.respond.send(result);
+
+            // Whichever of the worker and a timed-out caller flips the flag
+            // first decides: if the caller got there first, this job was
+            // counted in `STUCK_WORKERS` and the worker has now resolved it.
+            if job.done.swap(true, Ordering::SeqCst) {
+                STUCK_WORKERS.fetch_sub(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    fn run_job(
+        bytecode: &[u8],
+        chain: ChainSpec,
+        done: &Arc<AtomicBool>,
+    ) -> Result<Vec<StorageSlot>, String> {
+        let contract = Contract::new(bytecode.to_vec(), chain.into());
+
+        let outcome = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let watchdog = sle::watchdog::FlagWatchdog::new(done.clone())
+                .polling_every(100)
+                .in_rc();
+
+            sle::new(
+                contract,
+                sle::vm::Config::default(),
+                sle::tc::Config::default(),
+                watchdog,
+            )
+            .analyze()
+        }));
+
+        match outcome {
+            Ok(Ok(layout)) => {
+                let slots: Vec<sle::layout::StorageSlot> = layout.slots().iter().cloned().collect();
+                Ok(convert_storage_slots(&slots))
+            }
+            Ok(Err(e)) => {
+                if format!("{:?}", e).contains("StoppedByWatchdog") {
+                    Err("Storage extraction timed out".to_string())
+                } else {
+                    Err(format!("Storage extraction failed: {:?}", e))
+                }
+            }
+            Err(panic) => {
+                let panic_msg = if let Some(s) = panic.downcast_ref::<String>() {
+                    s.clone()
+                } else if let Some(s) = panic.downcast_ref::<&str>() {
+                    s.to_string()
+                } else {
+                    "Unknown panic during storage extraction".to_string()
+                };
+                Err(format!("Storage extraction panicked: {}", panic_msg))
+            }
+        }
+    }
+
+    /// Submits `bytecode` for storage-layout extraction, blocking the
+    /// caller up to `timeout` for a result. On timeout, flips the job's
+    /// watchdog flag so the worker cooperatively unwinds `analyze()` and
+    /// returns to the pool for its next job - the caller gives up, but the
+    /// worker thread itself is never abandoned.
+    pub fn extract(
+        &self,
+        bytecode: Vec<u8>,
+        chain: ChainSpec,
+        timeout: Duration,
+    ) -> (Vec<StorageSlot>, Option<String>) {
+        let done = Arc::new(AtomicBool::new(false));
+        let (respond_tx, respond_rx) = std::sync::mpsc::channel();
+
+        if self
+            .jobs
+            .send(Job {
+                bytecode,
+                chain,
+                done: done.clone(),
+                respond: respond_tx,
+            })
+            .is_err()
+        {
+            return (
+                Vec::new(),
+                Some("Storage worker pool has shut down".to_string()),
+            );
+        }
+
+        match respond_rx.recv_timeout(timeout) {
+            Ok(Ok(slots)) => (slots, None),
+            Ok(Err(e)) => (Vec::new(), Some(e)),
+            Err(_) => {
+                let now_stuck = STUCK_WORKERS.fetch_add(1, Ordering::Relaxed) + 1;
+                if done.swap(true, Ordering::SeqCst) {
+                    // The worker finished just after our deadline; it isn't stuck.
+                    STUCK_WORKERS.fetch_sub(1, Ordering::Relaxed);
+                } else {
+                    STUCK_WORKERS_PEAK.fetch_max(now_stuck, Ordering::Relaxed);
+                }
+
+                // Give the worker a grace period to notice the flag and
+                // unwind before reporting the timeout anyway; either way
+                // the worker thread returns to the pool rather than being
+                // abandoned, and `worker_loop` clears the stuck count once
+                // it actually finishes.
+                match respond_rx.recv_timeout(Duration::from_millis(100)) {
+                    Ok(Ok(slots)) => (slots, None),
+                    Ok(Err(e)) => (Vec::new(), Some(e)),
+                    Err(_) => (
+                        Vec::new(),
+                        Some(format!("Storage extraction timed out after {:?}", timeout)),
+                    ),
+                }
+            }
+        }
+    }
+}
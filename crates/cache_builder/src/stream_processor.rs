@@ -1,30 +1,81 @@
+use crate::bloom::BloomFilter;
 use crate::cache::AbiCache;
+use crate::chain::ChainSpec;
+use crate::contract_source::ContractSource;
+use crate::manifest::ProcessingManifest;
 use crate::parquet_reader::Contract;
-use crate::processor::{ContractProcessor, ABANDONED_THREADS};
-use crate::stats::Stats;
+use crate::processor::ContractProcessor;
+use crate::stats::{Durations, Stats};
+use crate::storage_pool::{StorageWorkerPool, DEFAULT_STORAGE_WORKER_POOL_SIZE};
 use crossbeam_channel::{bounded, Receiver, Sender};
 use eyre::Result;
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 use rayon::prelude::*;
-use std::collections::HashSet;
+use std::collections::VecDeque;
 use std::path::{Path, PathBuf};
-use std::sync::atomic::Ordering;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::{Duration, Instant};
+use std::time::Instant;
 use tokio::runtime::Runtime;
 use tracing::{debug, error, info, warn};
 
-const BATCH_SIZE: usize = 1000; // Process files in batches
 const CHANNEL_BUFFER: usize = 10000; // Buffer for work queue
+const DEFAULT_READER_THREADS: usize = 4;
+
+// Sizes the dedup bloom filter's bit array. With a 1% false-positive rate
+// a new bytecode that merely collides is still forwarded, since every
+// probable duplicate is confirmed with the exact `cache.exists` check; this
+// only bounds how often we pay that extra lookup. A duplicate whose first
+// copy is still being decompiled (not yet in the cache) is forwarded again.
+const BLOOM_EXPECTED_CONTRACTS: usize = 50_000_000;
+const BLOOM_FALSE_POSITIVE_RATE: f64 = 0.01;
+
+// A contract queued for decompilation, optionally tied back to the parquet
+// file it came from so the last worker to finish that file's contracts can
+// mark it complete in the processing manifest.
+struct QueuedContract {
+    contract: Contract,
+    completion: Option<Arc<FileCompletion>>,
+}
+
+// Tracks how many of a single parquet file's contracts are still in
+// flight; once the count hits zero, the file is atomically recorded in the
+// manifest as fully processed.
+struct FileCompletion {
+    path: PathBuf,
+    content_hash: String,
+    remaining: AtomicUsize,
+    manifest: Arc<Mutex<ProcessingManifest>>,
+}
+
+impl FileCompletion {
+    fn mark_one_done(&self) {
+        if self.remaining.fetch_sub(1, Ordering::AcqRel) == 1 {
+            if let Err(e) = self
+                .manifest
+                .lock()
+                .unwrap()
+                .mark_complete(&self.path, &self.content_hash)
+            {
+                warn!("Failed to update processing manifest for {:?}: {}", self.path, e);
+            }
+        }
+    }
+}
 
 pub struct StreamProcessor {
     cache: Arc<AbiCache>,
     stats: Arc<Stats>,
     workers: usize,
+    reader_threads: usize,
     timeout_secs: u64,
     skip_resolving: bool,
     extract_storage: bool,
+    resume: bool,
+    pattern: Option<String>,
+    chain: ChainSpec,
+    storage_pool_size: usize,
 }
 
 impl StreamProcessor {
@@ -40,23 +91,110 @@ impl StreamProcessor {
             cache,
             stats,
             workers,
+            reader_threads: DEFAULT_READER_THREADS,
             timeout_secs,
             skip_resolving,
             extract_storage,
+            resume: false,
+            pattern: None,
+            chain: ChainSpec::default(),
+            storage_pool_size: DEFAULT_STORAGE_WORKER_POOL_SIZE,
         }
     }
 
+    /// Size the bounded pool of parquet-reading threads independently of
+    /// `workers`, since a single reader thread otherwise starves a large
+    /// decompile worker pool.
+    pub fn with_reader_threads(mut self, reader_threads: usize) -> Self {
+        self.reader_threads = reader_threads.max(1);
+        self
+    }
+
+    /// Enable crash-resumable processing: `process_all_parquets` skips
+    /// files already recorded as complete in the parquet directory's
+    /// manifest sidecar, and records each file as complete once every one
+    /// of its contracts has drained through the decompile workers.
+    pub fn with_resume(mut self, resume: bool) -> Self {
+        self.resume = resume;
+        self
+    }
+
+    /// Restrict `process_all_parquets` to filenames matching a glob
+    /// `pattern` (`*`/`?` wildcards), e.g. `"ethereum__contracts__148*"`,
+    /// when pointed at a partitioned dataset root. Only the filename is
+    /// matched, not the partition directories above it.
+    pub fn with_pattern(mut self, pattern: impl Into<String>) -> Self {
+        self.pattern = Some(pattern.into());
+        self
+    }
+
+    /// Select which chain/EVM fork storage-layout extraction analyzes
+    /// contracts against; defaults to the latest supported fork.
+    pub fn with_chain(mut self, chain: ChainSpec) -> Self {
+        self.chain = chain;
+        self
+    }
+
+    /// Size the long-lived storage-extraction worker pool shared by every
+    /// decompile worker, bounding how many contracts can be mid-extraction
+    /// (or stuck past their timeout) at once.
+    pub fn with_storage_pool_size(mut self, storage_pool_size: usize) -> Self {
+        self.storage_pool_size = storage_pool_size.max(1);
+        self
+    }
+
     pub fn process_all_parquets(&self, parquet_dir: &Path) -> Result<()> {
         // Find all parquet files
         let parquet_files = self.find_parquet_files(parquet_dir)?;
-        let total_files = parquet_files.len();
 
-        if total_files == 0 {
+        if parquet_files.is_empty() {
             warn!("No parquet files found in {:?}", parquet_dir);
             return Ok(());
         }
 
-        info!("Found {} parquet files to process", total_files);
+        let manifest = if self.resume {
+            Some(Arc::new(Mutex::new(ProcessingManifest::load(parquet_dir)?)))
+        } else {
+            None
+        };
+
+        // Pair every file with its content hash up front: the hash both
+        // drives the resume skip-check below and is reused unchanged when
+        // the file is later marked complete.
+        let mut queue_items: Vec<(PathBuf, Option<String>)> = Vec::with_capacity(parquet_files.len());
+        let mut skipped = 0usize;
+        for path in parquet_files {
+            let content_hash = if manifest.is_some() {
+                Some(crate::manifest::hash_file(&path)?)
+            } else {
+                None
+            };
+
+            if let (Some(manifest), Some(hash)) = (&manifest, &content_hash) {
+                if manifest.lock().unwrap().is_complete(&path, hash) {
+                    skipped += 1;
+                    continue;
+                }
+            }
+
+            queue_items.push((path, content_hash));
+        }
+
+        let total_files = queue_items.len();
+        if total_files == 0 {
+            info!("All {} parquet files already processed (resume)", skipped);
+            return Ok(());
+        }
+
+        info!(
+            "Found {} parquet files to process{}",
+            total_files,
+            if skipped > 0 {
+                format!(" ({} already complete, skipped)", skipped)
+            } else {
+                String::new()
+            }
+        );
 
         // Set up progress bars
         let multi_progress = MultiProgress::new();
@@ -77,138 +215,239 @@ impl StreamProcessor {
         );
 
         // Channels for streaming contracts to workers
-        let (sender, receiver): (Sender<Contract>, Receiver<Contract>) = bounded(CHANNEL_BUFFER);
+        let (sender, receiver): (Sender<QueuedContract>, Receiver<QueuedContract>) =
+            bounded(CHANNEL_BUFFER);
+
+        // Shared state for deduplication and progress, pulled from
+        // concurrently by every reader thread below.
+        let seen_bytecodes = Arc::new(BloomFilter::new(BLOOM_EXPECTED_CONTRACTS, BLOOM_FALSE_POSITIVE_RATE));
+        let unique_count = Arc::new(AtomicUsize::new(0));
+        let duplicate_count = Arc::new(AtomicUsize::new(0));
+        let processed_files = Arc::new(AtomicUsize::new(0));
+        let total_contracts = Arc::new(AtomicUsize::new(0));
+
+        // A bounded pool of reader threads pulls file paths off this shared
+        // queue, so dozens of Rayon decompile workers aren't starved behind
+        // a single serial reader on large parquet directories.
+        let work_queue = Arc::new(Mutex::new(VecDeque::from(queue_items)));
+
+        let reader_handles: Vec<_> = (0..self.reader_threads)
+            .map(|_| {
+                let sender = sender.clone();
+                let seen_bytecodes = seen_bytecodes.clone();
+                let file_progress = file_progress.clone();
+                let contract_progress = contract_progress.clone();
+                let cache = self.cache.clone();
+                let unique_count = unique_count.clone();
+                let duplicate_count = duplicate_count.clone();
+                let processed_files = processed_files.clone();
+                let total_contracts = total_contracts.clone();
+                let work_queue = work_queue.clone();
+                let manifest = manifest.clone();
+                let skip_resolving = self.skip_resolving;
+
+                thread::spawn(move || loop {
+                    let (file_path, content_hash) = match work_queue.lock().unwrap().pop_front() {
+                        Some(item) => item,
+                        None => break,
+                    };
+
+                    match crate::parquet_reader::ParquetReader::read_contracts(&file_path) {
+                        Ok(contracts) => {
+                            total_contracts.fetch_add(contracts.len(), Ordering::Relaxed);
+
+                            let completion = match (&manifest, &content_hash) {
+                                (Some(manifest), Some(hash)) if !contracts.is_empty() => {
+                                    Some(Arc::new(FileCompletion {
+                                        path: file_path.clone(),
+                                        content_hash: hash.clone(),
+                                        remaining: AtomicUsize::new(contracts.len()),
+                                        manifest: manifest.clone(),
+                                    }))
+                                }
+                                _ => None,
+                            };
 
-        // Shared state for deduplication
-        let seen_bytecodes = Arc::new(Mutex::new(HashSet::new()));
-        let unique_count = Arc::new(std::sync::atomic::AtomicUsize::new(0));
-        let duplicate_count = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+                            if contracts.is_empty() {
+                                if let (Some(manifest), Some(hash)) = (&manifest, &content_hash) {
+                                    if let Err(e) = manifest.lock().unwrap().mark_complete(&file_path, hash) {
+                                        warn!("Failed to update processing manifest for {:?}: {}", file_path, e);
+                                    }
+                                }
+                            }
+
+                            for contract in contracts {
+                                // A probable duplicate still needs the exact
+                                // cache check to rule out a false positive;
+                                // a fresh bit pattern is new regardless of
+                                // the exact check (it can only get cheaper).
+                                let probably_seen = seen_bytecodes.check_and_set(contract.code.as_bytes());
+                                if probably_seen && cache.exists(&contract.code, skip_resolving) {
+                                    duplicate_count.fetch_add(1, Ordering::Relaxed);
+                                    if let Some(completion) = &completion {
+                                        completion.mark_one_done();
+                                    }
+                                    continue;
+                                }
+
+                                unique_count.fetch_add(1, Ordering::Relaxed);
+                                let queued = QueuedContract {
+                                    contract,
+                                    completion: completion.clone(),
+                                };
+                                if sender.send(queued).is_err() {
+                                    warn!("Worker channels closed, stopping reader");
+                                    return;
+                                }
+                            }
+
+                            let processed = processed_files.fetch_add(1, Ordering::Relaxed) + 1;
+                            file_progress.set_position(processed as u64);
+                            file_progress.set_message(format!(
+                                "{} unique, {} duplicates",
+                                unique_count.load(Ordering::Relaxed),
+                                duplicate_count.load(Ordering::Relaxed)
+                            ));
+
+                            contract_progress.set_length(total_contracts.load(Ordering::Relaxed) as u64);
+                            contract_progress.set_position(
+                                (unique_count.load(Ordering::Relaxed) + duplicate_count.load(Ordering::Relaxed)) as u64,
+                            );
+                        }
+                        Err(e) => {
+                            warn!("Failed to read {:?}: {}", file_path, e);
+                            let processed = processed_files.fetch_add(1, Ordering::Relaxed) + 1;
+                            file_progress.set_position(processed as u64);
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        // Drop the original sender so workers know when to stop
+        drop(sender);
+
+        self.run_workers(receiver, contract_progress.clone());
+
+        // Wait for all reader threads
+        for handle in reader_handles {
+            handle.join().expect("Reader thread panicked");
+        }
+
+        info!(
+            "Reader finished: {} files, {} contracts ({} unique, {} duplicates)",
+            processed_files.load(Ordering::Relaxed),
+            total_contracts.load(Ordering::Relaxed),
+            unique_count.load(Ordering::Relaxed),
+            duplicate_count.load(Ordering::Relaxed)
+        );
+
+        // Clear progress bars
+        file_progress.finish_with_message("Complete");
+        contract_progress.finish_with_message("Complete");
+
+        Ok(())
+    }
+
+    /// Process contracts pulled from any `ContractSource` (parquet exports,
+    /// a live JSON-RPC node, ...) through the same dedup + Rayon decompile
+    /// pipeline as `process_all_parquets`, minus the per-file progress
+    /// bookkeeping that only makes sense for a directory of files.
+    pub fn process_source(&self, source: Arc<dyn ContractSource>) -> Result<()> {
+        let contract_progress = ProgressBar::new(0);
+        contract_progress.set_style(
+            ProgressStyle::default_bar()
+                .template("[{elapsed_precise}] Contracts: {bar:40.green/blue} {pos} {msg}")
+                .unwrap()
+                .progress_chars("=>-"),
+        );
+
+        let (sender, receiver): (Sender<QueuedContract>, Receiver<QueuedContract>) =
+            bounded(CHANNEL_BUFFER);
+
+        let seen_bytecodes = Arc::new(BloomFilter::new(BLOOM_EXPECTED_CONTRACTS, BLOOM_FALSE_POSITIVE_RATE));
+        let unique_count = Arc::new(AtomicUsize::new(0));
+        let duplicate_count = Arc::new(AtomicUsize::new(0));
 
-        // Spawn reader thread that streams contracts from parquet files
         let reader_handle = {
-            let sender = sender.clone();
-            let seen_bytecodes = seen_bytecodes.clone();
-            let file_progress = file_progress.clone();
-            let contract_progress = contract_progress.clone();
             let cache = self.cache.clone();
-            let unique_count = unique_count.clone();
-            let duplicate_count = duplicate_count.clone();
             let skip_resolving = self.skip_resolving;
+            let contract_progress = contract_progress.clone();
 
             thread::spawn(move || {
-                let mut total_contracts = 0usize;
-                let mut processed_files = 0usize;
-
-                for (batch_idx, file_batch) in parquet_files.chunks(BATCH_SIZE).enumerate() {
-                    debug!("Processing batch {} ({} files)", batch_idx, file_batch.len());
-
-                    for file_path in file_batch {
-                        // Try to read the parquet file
-                        match crate::parquet_reader::ParquetReader::read_contracts(file_path) {
-                            Ok(contracts) => {
-                                let file_contract_count = contracts.len();
-                                total_contracts += file_contract_count;
-
-                                // Stream each contract through deduplication
-                                for contract in contracts {
-                                    // Check if we've seen this bytecode before
-                                    let is_duplicate = {
-                                        let mut seen = seen_bytecodes.lock().unwrap();
-                                        !seen.insert(contract.code.clone())
-                                    };
-
-                                    if is_duplicate {
-                                        duplicate_count.fetch_add(1, Ordering::Relaxed);
-                                        continue;
-                                    }
-
-                                    // Check if it's already in cache
-                                    if cache.exists(&contract.code, skip_resolving) {
-                                        duplicate_count.fetch_add(1, Ordering::Relaxed);
-                                        continue;
-                                    }
+                let (raw_sender, raw_receiver) = bounded::<Contract>(CHANNEL_BUFFER);
 
-                                    // Send unique contract to workers
-                                    unique_count.fetch_add(1, Ordering::Relaxed);
-                                    if sender.send(contract).is_err() {
-                                        warn!("Worker channels closed, stopping reader");
-                                        return;
-                                    }
-                                }
+                let source_thread = thread::spawn(move || {
+                    if let Err(e) = source.stream(raw_sender) {
+                        error!("Contract source failed: {}", e);
+                    }
+                });
 
-                                processed_files += 1;
-                                file_progress.set_position(processed_files as u64);
-                                file_progress.set_message(format!(
-                                    "{} unique, {} duplicates",
-                                    unique_count.load(Ordering::Relaxed),
-                                    duplicate_count.load(Ordering::Relaxed)
-                                ));
-
-                                contract_progress.set_length(total_contracts as u64);
-                                contract_progress.set_position(
-                                    (unique_count.load(Ordering::Relaxed) +
-                                     duplicate_count.load(Ordering::Relaxed)) as u64
-                                );
-                            }
-                            Err(e) => {
-                                warn!("Failed to read {:?}: {}", file_path, e);
-                                processed_files += 1;
-                                file_progress.set_position(processed_files as u64);
-                            }
-                        }
+                for contract in raw_receiver {
+                    let probably_seen = seen_bytecodes.check_and_set(contract.code.as_bytes());
+                    if probably_seen && cache.exists(&contract.code, skip_resolving) {
+                        duplicate_count.fetch_add(1, Ordering::Relaxed);
+                        continue;
+                    }
 
-                        // Periodically clear seen_bytecodes to prevent unbounded growth
-                        // We rely on cache to prevent reprocessing
-                        if processed_files % 1000 == 0 {
-                            let mut seen = seen_bytecodes.lock().unwrap();
-                            if seen.len() > 1_000_000 {
-                                debug!("Clearing seen bytecodes set (had {} entries)", seen.len());
-                                seen.clear();
-                            }
-                        }
+                    unique_count.fetch_add(1, Ordering::Relaxed);
+                    contract_progress.set_length(
+                        (unique_count.load(Ordering::Relaxed) + duplicate_count.load(Ordering::Relaxed)) as u64,
+                    );
+                    let queued = QueuedContract {
+                        contract,
+                        completion: None,
+                    };
+                    if sender.send(queued).is_err() {
+                        warn!("Worker channels closed, stopping reader");
+                        break;
                     }
                 }
 
-                info!(
-                    "Reader finished: {} files, {} contracts ({} unique, {} duplicates)",
-                    processed_files,
-                    total_contracts,
-                    unique_count.load(Ordering::Relaxed),
-                    duplicate_count.load(Ordering::Relaxed)
-                );
+                let _ = source_thread.join();
             })
         };
 
-        // Drop the original sender so workers know when to stop
-        drop(sender);
+        self.run_workers(receiver, contract_progress.clone());
+
+        reader_handle.join().expect("Reader thread panicked");
+        contract_progress.finish_with_message("Complete");
+
+        Ok(())
+    }
 
-        // Process contracts in parallel using Rayon
+    // Shared Rayon worker-pool body: decompiles every contract arriving on
+    // `receiver` and records its outcome in `self.stats`.
+    fn run_workers(&self, receiver: Receiver<QueuedContract>, contract_progress: ProgressBar) {
         let cache = self.cache.clone();
         let stats = self.stats.clone();
         let timeout = self.timeout_secs;
         let skip_resolving = self.skip_resolving;
         let extract_storage = self.extract_storage;
+        let chain = self.chain;
+        let storage_pool = Arc::new(StorageWorkerPool::new(self.storage_pool_size));
 
-        // Set up Rayon thread pool
         rayon::ThreadPoolBuilder::new()
             .num_threads(self.workers)
             .thread_name(|i| format!("worker-{}", i))
             .build()
             .unwrap()
             .install(|| {
-                // Process contracts from the channel
-                receiver.into_iter().par_bridge().for_each(|contract| {
-                    // Create thread-local Tokio runtime
+                receiver.into_iter().par_bridge().for_each(|queued| {
                     thread_local! {
                         static RUNTIME: Runtime = Runtime::new().expect("Failed to create runtime");
                     }
 
+                    let QueuedContract { contract, completion } = queued;
+
                     RUNTIME.with(|rt| {
                         let processor = ContractProcessor::new(
                             cache.clone(),
                             timeout,
                             skip_resolving,
                             extract_storage,
+                            chain,
+                            storage_pool.clone(),
                         );
 
                         let result = rt.block_on(async {
@@ -227,7 +466,7 @@ impl StreamProcessor {
                                     process_result.cached,
                                     process_result.success,
                                     is_timeout,
-                                    process_result.duration,
+                                    process_result.durations,
                                 );
 
                                 contract_progress.inc(1);
@@ -240,43 +479,20 @@ impl StreamProcessor {
                             }
                             Err(e) => {
                                 error!("Failed to process contract: {}", e);
-                                stats.record_result(false, false, false, Duration::ZERO);
+                                stats.record_result(false, false, false, Durations::default());
                                 contract_progress.inc(1);
                             }
                         }
                     });
+
+                    if let Some(completion) = completion {
+                        completion.mark_one_done();
+                    }
                 });
             });
-
-        // Wait for reader thread
-        reader_handle.join().expect("Reader thread panicked");
-
-        // Clear progress bars
-        file_progress.finish_with_message("Complete");
-        contract_progress.finish_with_message("Complete");
-
-        Ok(())
     }
 
     fn find_parquet_files(&self, directory: &Path) -> Result<Vec<PathBuf>> {
-        let mut parquet_files = Vec::new();
-
-        if !directory.exists() {
-            return Err(eyre::eyre!("Directory does not exist: {:?}", directory));
-        }
-
-        for entry in std::fs::read_dir(directory)? {
-            let entry = entry?;
-            let path = entry.path();
-
-            if path.extension().and_then(|s| s.to_str()) == Some("parquet") {
-                parquet_files.push(path);
-            }
-        }
-
-        // Sort for consistent ordering
-        parquet_files.sort();
-
-        Ok(parquet_files)
+        crate::parquet_reader::discover_parquet_files(directory, self.pattern.as_deref())
     }
 }
\ No newline at end of file
@@ -0,0 +1,271 @@
+// Tails a live chain for newly deployed contracts instead of walking a
+// fixed historical range. Subscribes to a node's `newHeads` pub/sub feed
+// over a websocket, and for each incoming head fetches the block's
+// contract-creation transactions and their deployed bytecode the same way
+// `Ingester` does for a block range, feeding them through the same
+// `ContractProcessor`/`AbiCache` pipeline in real time.
+
+use crate::cache::AbiCache;
+use crate::chain::ChainSpec;
+use crate::processor::{ContractProcessor, ProcessResult};
+use crate::stats::Stats;
+use crate::storage_pool::StorageWorkerPool;
+use crossbeam_channel::{bounded, Receiver, Sender};
+use eyre::{bail, Result};
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio::runtime::Runtime;
+use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
+use tracing::{debug, warn};
+
+const RESULT_CHANNEL_BUFFER: usize = 10000;
+const RECONNECT_BACKOFF: Duration = Duration::from_secs(2);
+
+pub struct SubscribeConfig {
+    pub ws_url: String,
+    pub timeout_secs: u64,
+    pub skip_resolving: bool,
+    pub extract_storage: bool,
+    pub chain: ChainSpec,
+    /// Size of the long-lived storage-extraction worker pool shared by
+    /// every contract this `Subscriber` processes.
+    pub storage_pool_size: usize,
+}
+
+/// Tails freshly deployed contracts off a node's `newHeads` subscription.
+/// Unlike `Ingester` there's no block range or cursor to checkpoint - a
+/// dropped connection resubscribes and simply picks up whatever head the
+/// node reports next, same as any other `newHeads` consumer.
+pub struct Subscriber {
+    cache: Arc<AbiCache>,
+    stats: Arc<Stats>,
+    config: SubscribeConfig,
+}
+
+impl Subscriber {
+    pub fn new(cache: Arc<AbiCache>, stats: Arc<Stats>, config: SubscribeConfig) -> Self {
+        Subscriber {
+            cache,
+            stats,
+            config,
+        }
+    }
+
+    /// Connects in the background and returns a channel that yields a
+    /// `ProcessResult` for every freshly deployed contract as it's
+    /// decompiled. Runs until the returned receiver is dropped, transparently
+    /// reconnecting and resubscribing on socket drop.
+    pub fn run(self) -> Receiver<ProcessResult> {
+        let (tx, rx) = bounded(RESULT_CHANNEL_BUFFER);
+
+        thread::spawn(move || {
+            let runtime = Runtime::new().expect("Failed to create runtime");
+            runtime.block_on(self.run_with_reconnect(tx));
+        });
+
+        rx
+    }
+
+    async fn run_with_reconnect(&self, result_tx: Sender<ProcessResult>) {
+        let storage_pool = Arc::new(StorageWorkerPool::new(self.config.storage_pool_size));
+        let processor = Arc::new(ContractProcessor::new(
+            self.cache.clone(),
+            self.config.timeout_secs,
+            self.config.skip_resolving,
+            self.config.extract_storage,
+            self.config.chain,
+            storage_pool,
+        ));
+
+        loop {
+            match self.subscribe_once(&processor, &result_tx).await {
+                Ok(()) => {
+                    debug!("Result receiver dropped, stopping subscription");
+                    return;
+                }
+                Err(e) => warn!(
+                    "Subscription dropped ({}), reconnecting in {:?}",
+                    e, RECONNECT_BACKOFF
+                ),
+            }
+
+            tokio::time::sleep(RECONNECT_BACKOFF).await;
+        }
+    }
+
+    async fn subscribe_once(
+        &self,
+        processor: &Arc<ContractProcessor>,
+        result_tx: &Sender<ProcessResult>,
+    ) -> Result<()> {
+        let mut client = WsClient::connect(&self.config.ws_url).await?;
+        client.call("eth_subscribe", json!(["newHeads"])).await?;
+
+        loop {
+            let notification = client.next_notification().await?;
+            let Some(header) = notification
+                .get("params")
+                .and_then(|p| p.get("result"))
+            else {
+                continue;
+            };
+            let Some(block_number_hex) = header.get("number").and_then(|n| n.as_str()) else {
+                continue;
+            };
+
+            let creations = match self
+                .fetch_contract_creations(&mut client, block_number_hex)
+                .await
+            {
+                Ok(creations) => creations,
+                Err(e) => {
+                    warn!(
+                        "Failed to fetch contract creations for block {}: {}",
+                        block_number_hex, e
+                    );
+                    continue;
+                }
+            };
+
+            for (address, code) in creations {
+                let result = processor.process_contract(address, code).await?;
+                self.stats.record_result(
+                    result.cached,
+                    result.success,
+                    result
+                        .error
+                        .as_deref()
+                        .is_some_and(|e| e.contains("timed out")),
+                    result.durations,
+                );
+
+                if result_tx.send(result).is_err() {
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    async fn fetch_contract_creations(
+        &self,
+        client: &mut WsClient,
+        block_number_hex: &str,
+    ) -> Result<Vec<(String, String)>> {
+        let block = client
+            .call("eth_getBlockByNumber", json!([block_number_hex, true]))
+            .await?;
+
+        let transactions = match block.get("transactions").and_then(|t| t.as_array()) {
+            Some(txs) => txs.clone(),
+            None => bail!("Block {} has no transactions field", block_number_hex),
+        };
+
+        let creation_hashes: Vec<String> = transactions
+            .iter()
+            .filter(|tx| tx.get("to").map(|to| to.is_null()).unwrap_or(true))
+            .filter_map(|tx| tx.get("hash").and_then(|h| h.as_str()).map(str::to_string))
+            .collect();
+
+        let mut creations = Vec::with_capacity(creation_hashes.len());
+        for hash in creation_hashes {
+            let receipt = client
+                .call("eth_getTransactionReceipt", json!([hash]))
+                .await?;
+            let address = match receipt.get("contractAddress").and_then(|a| a.as_str()) {
+                Some(address) => address.to_string(),
+                None => continue,
+            };
+
+            let code = client
+                .call("eth_getCode", json!([address, "latest"]))
+                .await?;
+            let code = code.as_str().unwrap_or("0x").to_string();
+            if code.is_empty() || code == "0x" {
+                continue;
+            }
+
+            creations.push((address, code));
+        }
+
+        Ok(creations)
+    }
+}
+
+/// A websocket JSON-RPC connection used for both the `newHeads`
+/// subscription and ordinary request/response calls (e.g.
+/// `eth_getBlockByNumber`) needed to resolve each head into contract
+/// creations. Since everything runs on a single socket, a notification
+/// that arrives while a call is awaiting its response is queued rather
+/// than dropped.
+struct WsClient {
+    stream: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    next_id: u64,
+    pending_notifications: VecDeque<Value>,
+}
+
+impl WsClient {
+    async fn connect(url: &str) -> Result<Self> {
+        let (stream, _) = connect_async(url).await?;
+        Ok(WsClient {
+            stream,
+            next_id: 1,
+            pending_notifications: VecDeque::new(),
+        })
+    }
+
+    async fn call(&mut self, method: &str, params: Value) -> Result<Value> {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": method,
+            "params": params,
+        });
+        self.stream.send(Message::Text(request.to_string())).await?;
+
+        loop {
+            let message = self
+                .stream
+                .next()
+                .await
+                .ok_or_else(|| eyre::eyre!("Socket closed while awaiting {} response", method))??;
+            let Message::Text(text) = message else { continue };
+            let value: Value = serde_json::from_str(&text)?;
+
+            if value.get("id").and_then(|v| v.as_u64()) == Some(id) {
+                if let Some(error) = value.get("error") {
+                    bail!("{} failed: {:?}", method, error);
+                }
+                return Ok(value.get("result").cloned().unwrap_or(Value::Null));
+            }
+
+            // Not our response - a subscription notification arrived while
+            // we were waiting; queue it for the next `next_notification`.
+            self.pending_notifications.push_back(value);
+        }
+    }
+
+    async fn next_notification(&mut self) -> Result<Value> {
+        if let Some(value) = self.pending_notifications.pop_front() {
+            return Ok(value);
+        }
+
+        loop {
+            let message = self
+                .stream
+                .next()
+                .await
+                .ok_or_else(|| eyre::eyre!("Socket closed while awaiting a notification"))??;
+            if let Message::Text(text) = message {
+                return Ok(serde_json::from_str(&text)?);
+            }
+        }
+    }
+}
@@ -51,22 +51,76 @@ pub struct ABIError {
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct StorageSlot {
-    pub index: u64,
+    // Full 256-bit slot index (decimal), since keccak-derived mapping/dynamic
+    // array slots don't fit in a u64.
+    pub index: String,
     pub offset: u32,
     pub typ: String,
+    // "plain" | "mapping" | "dynamic_array" | "packed"
+    pub kind: String,
+    // Populated only when `kind == "mapping"`.
+    pub mapping_key_type: Option<String>,
 }
 
-impl From<sle::layout::StorageSlot> for StorageSlot {
-    fn from(slot: sle::layout::StorageSlot) -> Self {
-        let index_str = format!("{:?}", slot.index);
-        let index = index_str.parse::<u64>().unwrap_or(0);
+// Converts the extractor's raw per-slot layout into our `StorageSlot` wire
+// format, classifying each slot's `kind`. Unlike the rest of a slot's data,
+// "packed" can't be determined from a single slot's own `offset` - a packed
+// struct's *first* member sits at `offset == 0` too, same as an ordinary
+// plain slot, and only looking at its siblings at the same storage index
+// reveals that the slot is shared. So this takes the full layout and groups
+// by `index` first, instead of converting one `sle::layout::StorageSlot` at
+// a time.
+pub fn convert_storage_slots(slots: &[sle::layout::StorageSlot]) -> Vec<StorageSlot> {
+    let mut slots_at_index: std::collections::HashMap<String, usize> = std::collections::HashMap::new();
+    for slot in slots {
+        *slots_at_index.entry(format!("{:?}", slot.index)).or_insert(0) += 1;
+    }
+
+    slots
+        .iter()
+        .filter(|slot| slot.typ.to_solidity_type() != "unknown")
+        .map(|slot| {
+            // Keep the full-precision decimal index instead of truncating
+            // into a u64, which silently collapsed every keccak-derived
+            // slot to 0.
+            let index = format!("{:?}", slot.index);
+            let offset = slot.offset as u32;
+            let typ = slot.typ.to_solidity_type();
+            let is_packed = slots_at_index.get(&index).copied().unwrap_or(1) > 1;
+            let (kind, mapping_key_type) = classify_storage_slot(&typ, is_packed);
+
+            StorageSlot {
+                index,
+                offset,
+                typ,
+                kind,
+                mapping_key_type,
+            }
+        })
+        .collect()
+}
 
-        StorageSlot {
-            index,
-            offset: slot.offset as u32,
-            typ: slot.typ.to_solidity_type(),
+// Classifies a storage slot's layout kind from its Solidity type string
+// (mapping, with the key type, or dynamic array) and, for everything else,
+// whether it shares its slot index with sibling fields (packed) or not
+// (plain).
+fn classify_storage_slot(typ: &str, is_packed: bool) -> (String, Option<String>) {
+    if let Some(inner) = typ.strip_prefix("mapping(") {
+        if let Some(arrow) = inner.find("=>") {
+            let key_type = inner[..arrow].trim().to_string();
+            return ("mapping".to_string(), Some(key_type));
         }
     }
+
+    if typ.ends_with("[]") {
+        return ("dynamic_array".to_string(), None);
+    }
+
+    if is_packed {
+        return ("packed".to_string(), None);
+    }
+
+    ("plain".to_string(), None)
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -0,0 +1,612 @@
+// Implements the standard Solidity head/tail ABI encoding scheme against
+// Python-native values: static types (`uintN`/`intN`/`bool`/`address`/
+// `bytesN`) occupy one 32-byte word each; dynamic types (`bytes`, `string`,
+// `T[]`, and any tuple containing a dynamic element) write a 32-byte offset
+// in the head pointing into the tail, where length-prefixed or element data
+// is laid out. `ABIParam::type_` strings (already normalized by
+// `collapse_if_tuple`, e.g. `(uint256,bytes)[]`) are parsed into a type
+// tree the coder walks recursively.
+//
+// Integers are converted via Python's own `int.from_bytes`/`to_bytes`
+// rather than a hand-rolled bignum type, since Python ints are already
+// arbitrary-precision.
+
+use crate::{ABIEvent, ABIFunction, ABI};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict, PyList};
+use tiny_keccak::{Hasher, Keccak};
+
+pub fn encode_function_input(py: Python, function: &ABIFunction, args: &PyAny) -> PyResult<Vec<u8>> {
+    let types: Vec<AbiType> = function
+        .inputs
+        .iter()
+        .map(|p| parse_type(&p.type_))
+        .collect::<PyResult<_>>()?;
+
+    let values: Vec<&PyAny> = args.extract()?;
+    if values.len() != types.len() {
+        return Err(PyValueError::new_err(format!(
+            "{} expects {} arguments, got {}",
+            function.name,
+            types.len(),
+            values.len()
+        )));
+    }
+
+    let mut encoded = function.selector.to_vec();
+    encoded.extend(encode_head_tail(py, &values, &types)?);
+    Ok(encoded)
+}
+
+pub fn decode_function_input(py: Python, abi: &ABI, data: &[u8]) -> PyResult<(ABIFunction, PyObject)> {
+    if data.len() < 4 {
+        return Err(PyValueError::new_err(format!(
+            "calldata is {} bytes, shorter than the 4-byte selector",
+            data.len()
+        )));
+    }
+
+    let selector: [u8; 4] = data[..4].try_into().unwrap();
+    let &idx = abi
+        .by_selector
+        .get(&selector)
+        .ok_or_else(|| PyValueError::new_err(format!("no function matches selector 0x{}", hex::encode(selector))))?;
+    let function = abi.functions[idx].clone();
+
+    let types: Vec<AbiType> = function
+        .inputs
+        .iter()
+        .map(|p| parse_type(&p.type_))
+        .collect::<PyResult<_>>()?;
+
+    let values = decode_head_tail(py, &types, &data[4..], 0)?;
+    Ok((function, PyList::new(py, values).into()))
+}
+
+pub fn decode_log(py: Python, abi: &ABI, topics: Vec<Vec<u8>>, data: &[u8]) -> PyResult<(ABIEvent, PyObject)> {
+    let topic0: &Vec<u8> = topics
+        .first()
+        .ok_or_else(|| PyValueError::new_err("log has no topics to match against topic0"))?;
+    let topic0: [u8; 32] = topic0
+        .as_slice()
+        .try_into()
+        .map_err(|_| PyValueError::new_err(format!("topic0 must be 32 bytes, got {}", topic0.len())))?;
+
+    let &idx = abi
+        .by_topic0
+        .get(&topic0)
+        .ok_or_else(|| PyValueError::new_err(format!("no event matches topic0 0x{}", hex::encode(topic0))))?;
+    let event = abi.events[idx].clone();
+
+    let indexed_params: Vec<_> = event.inputs.iter().filter(|p| p.indexed).collect();
+    let non_indexed_params: Vec<_> = event.inputs.iter().filter(|p| !p.indexed).collect();
+    let indexed_topics = &topics[1..];
+
+    if indexed_params.len() != indexed_topics.len() {
+        return Err(PyValueError::new_err(format!(
+            "event {} has {} indexed parameters, but {} extra topics were provided",
+            event.name,
+            indexed_params.len(),
+            indexed_topics.len()
+        )));
+    }
+
+    let result = PyDict::new(py);
+    for (param, topic) in indexed_params.iter().zip(indexed_topics) {
+        let ty = parse_type(&param.type_)?;
+        let value = if is_dynamic(&ty) {
+            // The original value isn't recoverable from a dynamic indexed
+            // parameter's topic - it only carries keccak256(value).
+            PyBytes::new(py, topic).into()
+        } else {
+            decode_static(py, &ty, topic, 0)?.0
+        };
+        result.set_item(&param.name, value)?;
+    }
+
+    let non_indexed_types: Vec<AbiType> = non_indexed_params
+        .iter()
+        .map(|p| parse_type(&p.type_))
+        .collect::<PyResult<_>>()?;
+    let values = decode_head_tail(py, &non_indexed_types, data, 0)?;
+    for (param, value) in non_indexed_params.iter().zip(values) {
+        result.set_item(&param.name, value)?;
+    }
+
+    Ok((event, result.into()))
+}
+
+pub fn decode_function_output(py: Python, function: &ABIFunction, data: &[u8]) -> PyResult<PyObject> {
+    let types: Vec<AbiType> = function
+        .outputs
+        .iter()
+        .map(|p| parse_type(&p.type_))
+        .collect::<PyResult<_>>()?;
+
+    let values = decode_head_tail(py, &types, data, 0)?;
+    Ok(PyList::new(py, values).into())
+}
+
+// ---- ABI type tree -------------------------------------------------------
+
+#[derive(Clone, Debug)]
+enum AbiType {
+    Uint(usize),
+    Int(usize),
+    Bool,
+    Address,
+    FixedBytes(usize),
+    Bytes,
+    Str,
+    Array(Box<AbiType>),
+    FixedArray(Box<AbiType>, usize),
+    Tuple(Vec<AbiType>),
+}
+
+fn parse_type(s: &str) -> PyResult<AbiType> {
+    let s = s.trim();
+
+    if s.starts_with('(') {
+        let close = find_matching_paren(s)?;
+        let components = split_top_level(&s[1..close]);
+        let fields = components
+            .iter()
+            .map(|c| parse_type(c))
+            .collect::<PyResult<Vec<_>>>()?;
+        apply_array_suffix(AbiType::Tuple(fields), &s[close + 1..])
+    } else {
+        let (base_str, suffix) = match s.find('[') {
+            Some(i) => (&s[..i], &s[i..]),
+            None => (s, ""),
+        };
+        apply_array_suffix(parse_elementary(base_str)?, suffix)
+    }
+}
+
+pub(crate) fn find_matching_paren(s: &str) -> PyResult<usize> {
+    let mut depth = 0i32;
+    for (i, c) in s.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Ok(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    Err(PyValueError::new_err(format!("unbalanced parentheses in ABI type string: {}", s)))
+}
+
+pub(crate) fn split_top_level(s: &str) -> Vec<String> {
+    if s.is_empty() {
+        return Vec::new();
+    }
+
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut start = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(s[start..i].to_string());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(s[start..].to_string());
+    parts
+}
+
+fn apply_array_suffix(mut base: AbiType, suffix: &str) -> PyResult<AbiType> {
+    let mut rest = suffix;
+    while !rest.is_empty() {
+        if !rest.starts_with('[') {
+            return Err(PyValueError::new_err(format!("malformed array suffix in ABI type string: {}", suffix)));
+        }
+        let close = rest
+            .find(']')
+            .ok_or_else(|| PyValueError::new_err(format!("unterminated array suffix in ABI type string: {}", suffix)))?;
+        let len_str = &rest[1..close];
+
+        base = if len_str.is_empty() {
+            AbiType::Array(Box::new(base))
+        } else {
+            let n: usize = len_str
+                .parse()
+                .map_err(|_| PyValueError::new_err(format!("invalid fixed array length: {}", len_str)))?;
+            AbiType::FixedArray(Box::new(base), n)
+        };
+
+        rest = &rest[close + 1..];
+    }
+    Ok(base)
+}
+
+fn parse_elementary(s: &str) -> PyResult<AbiType> {
+    Ok(match s {
+        "uint" => AbiType::Uint(256),
+        "int" => AbiType::Int(256),
+        "bool" => AbiType::Bool,
+        "address" => AbiType::Address,
+        "bytes" => AbiType::Bytes,
+        "string" => AbiType::Str,
+        _ if s.starts_with("uint") => AbiType::Uint(
+            s[4..]
+                .parse()
+                .map_err(|_| PyValueError::new_err(format!("invalid uint width: {}", s)))?,
+        ),
+        _ if s.starts_with("int") => AbiType::Int(
+            s[3..]
+                .parse()
+                .map_err(|_| PyValueError::new_err(format!("invalid int width: {}", s)))?,
+        ),
+        _ if s.starts_with("bytes") => AbiType::FixedBytes(
+            s[5..]
+                .parse()
+                .map_err(|_| PyValueError::new_err(format!("invalid bytesN width: {}", s)))?,
+        ),
+        _ => return Err(PyValueError::new_err(format!("unsupported ABI type: {}", s))),
+    })
+}
+
+fn is_dynamic(t: &AbiType) -> bool {
+    match t {
+        AbiType::Bytes | AbiType::Str | AbiType::Array(_) => true,
+        AbiType::FixedArray(inner, _) => is_dynamic(inner),
+        AbiType::Tuple(fields) => fields.iter().any(is_dynamic),
+        _ => false,
+    }
+}
+
+// ---- encoding -------------------------------------------------------------
+
+fn encode_head_tail(py: Python, values: &[&PyAny], types: &[AbiType]) -> PyResult<Vec<u8>> {
+    if values.len() != types.len() {
+        return Err(PyValueError::new_err(format!(
+            "expected {} values, got {}",
+            types.len(),
+            values.len()
+        )));
+    }
+
+    let mut head_parts = Vec::with_capacity(types.len());
+    let mut tail_parts = Vec::with_capacity(types.len());
+
+    for (value, ty) in values.iter().zip(types) {
+        if is_dynamic(ty) {
+            head_parts.push(Vec::new());
+            tail_parts.push(encode_dynamic(py, value, ty)?);
+        } else {
+            head_parts.push(encode_static(py, value, ty)?);
+            tail_parts.push(Vec::new());
+        }
+    }
+
+    let head_len: usize = types
+        .iter()
+        .enumerate()
+        .map(|(i, ty)| if is_dynamic(ty) { 32 } else { head_parts[i].len() })
+        .sum();
+
+    let mut head_bytes = Vec::with_capacity(head_len);
+    let mut tail_offset = head_len;
+    for (i, ty) in types.iter().enumerate() {
+        if is_dynamic(ty) {
+            head_bytes.extend_from_slice(&usize_to_word(tail_offset));
+            tail_offset += tail_parts[i].len();
+        } else {
+            head_bytes.extend_from_slice(&head_parts[i]);
+        }
+    }
+
+    let mut result = head_bytes;
+    for tail in tail_parts {
+        result.extend(tail);
+    }
+    Ok(result)
+}
+
+fn encode_static(py: Python, value: &PyAny, t: &AbiType) -> PyResult<Vec<u8>> {
+    match t {
+        AbiType::Uint(_) => Ok(py_int_to_word(py, value, false)?.to_vec()),
+        AbiType::Int(_) => Ok(py_int_to_word(py, value, true)?.to_vec()),
+        AbiType::Bool => {
+            let b: bool = value.extract()?;
+            let mut word = [0u8; 32];
+            word[31] = b as u8;
+            Ok(word.to_vec())
+        }
+        AbiType::Address => {
+            let addr: String = value.extract()?;
+            let addr = addr.strip_prefix("0x").unwrap_or(&addr);
+            let bytes = hex::decode(addr).map_err(|e| PyValueError::new_err(format!("invalid address: {}", e)))?;
+            if bytes.len() != 20 {
+                return Err(PyValueError::new_err(format!("address must be 20 bytes, got {}", bytes.len())));
+            }
+            let mut word = [0u8; 32];
+            word[12..].copy_from_slice(&bytes);
+            Ok(word.to_vec())
+        }
+        AbiType::FixedBytes(n) => {
+            let bytes: Vec<u8> = value.extract()?;
+            if bytes.len() > *n {
+                return Err(PyValueError::new_err(format!("bytes{} value has {} bytes", n, bytes.len())));
+            }
+            let mut word = [0u8; 32];
+            word[..bytes.len()].copy_from_slice(&bytes);
+            Ok(word.to_vec())
+        }
+        AbiType::Tuple(fields) => {
+            let values: Vec<&PyAny> = value.extract()?;
+            if values.len() != fields.len() {
+                return Err(PyValueError::new_err(format!(
+                    "tuple expects {} elements, got {}",
+                    fields.len(),
+                    values.len()
+                )));
+            }
+            let mut out = Vec::new();
+            for (v, f) in values.iter().zip(fields) {
+                out.extend(encode_static(py, v, f)?);
+            }
+            Ok(out)
+        }
+        AbiType::FixedArray(inner, n) => {
+            let values: Vec<&PyAny> = value.extract()?;
+            if values.len() != *n {
+                return Err(PyValueError::new_err(format!("expected {} elements, got {}", n, values.len())));
+            }
+            let mut out = Vec::new();
+            for v in &values {
+                out.extend(encode_static(py, v, inner)?);
+            }
+            Ok(out)
+        }
+        AbiType::Bytes | AbiType::Str | AbiType::Array(_) => {
+            Err(PyValueError::new_err("internal error: dynamic type reached encode_static"))
+        }
+    }
+}
+
+fn encode_dynamic(py: Python, value: &PyAny, t: &AbiType) -> PyResult<Vec<u8>> {
+    match t {
+        AbiType::Bytes => {
+            let bytes: Vec<u8> = value.extract()?;
+            let mut out = usize_to_word(bytes.len()).to_vec();
+            out.extend(pad32(bytes));
+            Ok(out)
+        }
+        AbiType::Str => {
+            let s: String = value.extract()?;
+            let bytes = s.into_bytes();
+            let mut out = usize_to_word(bytes.len()).to_vec();
+            out.extend(pad32(bytes));
+            Ok(out)
+        }
+        AbiType::Array(inner) => {
+            let values: Vec<&PyAny> = value.extract()?;
+            let types: Vec<AbiType> = std::iter::repeat((**inner).clone()).take(values.len()).collect();
+            let mut out = usize_to_word(values.len()).to_vec();
+            out.extend(encode_head_tail(py, &values, &types)?);
+            Ok(out)
+        }
+        AbiType::FixedArray(inner, n) => {
+            let values: Vec<&PyAny> = value.extract()?;
+            if values.len() != *n {
+                return Err(PyValueError::new_err(format!("expected {} elements, got {}", n, values.len())));
+            }
+            let types: Vec<AbiType> = std::iter::repeat((**inner).clone()).take(*n).collect();
+            encode_head_tail(py, &values, &types)
+        }
+        AbiType::Tuple(fields) => {
+            let values: Vec<&PyAny> = value.extract()?;
+            encode_head_tail(py, &values, fields)
+        }
+        _ => Err(PyValueError::new_err("internal error: static type reached encode_dynamic")),
+    }
+}
+
+fn pad32(mut bytes: Vec<u8>) -> Vec<u8> {
+    let rem = bytes.len() % 32;
+    if rem != 0 {
+        bytes.extend(std::iter::repeat(0u8).take(32 - rem));
+    }
+    bytes
+}
+
+fn usize_to_word(n: usize) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[24..].copy_from_slice(&(n as u64).to_be_bytes());
+    word
+}
+
+fn py_int_to_word(py: Python, value: &PyAny, signed: bool) -> PyResult<[u8; 32]> {
+    let kwargs = PyDict::new(py);
+    kwargs.set_item("signed", signed)?;
+    let bytes_obj = value.call_method("to_bytes", (32usize, "big"), Some(kwargs))?;
+    let bytes: &PyBytes = bytes_obj.downcast()?;
+    let mut word = [0u8; 32];
+    word.copy_from_slice(bytes.as_bytes());
+    Ok(word)
+}
+
+// ---- decoding --------------------------------------------------------------
+
+fn decode_head_tail(py: Python, types: &[AbiType], data: &[u8], base: usize) -> PyResult<Vec<PyObject>> {
+    let mut head_cursor = base;
+    let mut values = Vec::with_capacity(types.len());
+
+    for t in types {
+        if is_dynamic(t) {
+            let offset = read_usize(data, head_cursor)?;
+            let tail_pos = base
+                .checked_add(offset)
+                .ok_or_else(|| PyValueError::new_err("tail offset overflow"))?;
+            values.push(decode_dynamic(py, t, data, tail_pos)?);
+            head_cursor += 32;
+        } else {
+            let (value, consumed) = decode_static(py, t, data, head_cursor)?;
+            values.push(value);
+            head_cursor += consumed;
+        }
+    }
+
+    Ok(values)
+}
+
+fn decode_static(py: Python, t: &AbiType, data: &[u8], at: usize) -> PyResult<(PyObject, usize)> {
+    match t {
+        AbiType::Uint(_) => Ok((word_to_pyint(py, read_word(data, at)?, false)?, 32)),
+        AbiType::Int(_) => Ok((word_to_pyint(py, read_word(data, at)?, true)?, 32)),
+        AbiType::Bool => Ok(((read_word(data, at)?[31] != 0).into_py(py), 32)),
+        AbiType::Address => {
+            let word = read_word(data, at)?;
+            Ok((checksum_address(&word[12..32]).into_py(py), 32))
+        }
+        AbiType::FixedBytes(n) => {
+            let word = read_word(data, at)?;
+            Ok((PyBytes::new(py, &word[..*n]).into(), 32))
+        }
+        AbiType::Tuple(fields) => {
+            let mut cursor = at;
+            let mut values = Vec::with_capacity(fields.len());
+            for field in fields {
+                let (value, consumed) = decode_static(py, field, data, cursor)?;
+                values.push(value);
+                cursor += consumed;
+            }
+            Ok((PyList::new(py, values).into(), cursor - at))
+        }
+        AbiType::FixedArray(inner, n) => {
+            let mut cursor = at;
+            let mut values = Vec::with_capacity(*n);
+            for _ in 0..*n {
+                let (value, consumed) = decode_static(py, inner, data, cursor)?;
+                values.push(value);
+                cursor += consumed;
+            }
+            Ok((PyList::new(py, values).into(), cursor - at))
+        }
+        AbiType::Bytes | AbiType::Str | AbiType::Array(_) => {
+            Err(PyValueError::new_err("internal error: dynamic type reached decode_static"))
+        }
+    }
+}
+
+fn decode_dynamic(py: Python, t: &AbiType, data: &[u8], at: usize) -> PyResult<PyObject> {
+    match t {
+        AbiType::Bytes => {
+            let len = read_usize(data, at)?;
+            let data_start = at.checked_add(32).ok_or_else(|| PyValueError::new_err("bytes data offset overflow"))?;
+            let bytes = read_bytes(data, data_start, len)?;
+            Ok(PyBytes::new(py, bytes).into())
+        }
+        AbiType::Str => {
+            let len = read_usize(data, at)?;
+            let data_start = at.checked_add(32).ok_or_else(|| PyValueError::new_err("string data offset overflow"))?;
+            let bytes = read_bytes(data, data_start, len)?;
+            Ok(String::from_utf8_lossy(bytes).into_owned().into_py(py))
+        }
+        AbiType::Array(inner) => {
+            let len = read_usize(data, at)?;
+            let elements_start = at.checked_add(32).ok_or_else(|| PyValueError::new_err("array data offset overflow"))?;
+            check_array_len(data, elements_start, len)?;
+            let types: Vec<AbiType> = std::iter::repeat((**inner).clone()).take(len).collect();
+            Ok(PyList::new(py, decode_head_tail(py, &types, data, elements_start)?).into())
+        }
+        AbiType::FixedArray(inner, n) => {
+            let types: Vec<AbiType> = std::iter::repeat((**inner).clone()).take(*n).collect();
+            Ok(PyList::new(py, decode_head_tail(py, &types, data, at)?).into())
+        }
+        AbiType::Tuple(fields) => Ok(PyList::new(py, decode_head_tail(py, fields, data, at)?).into()),
+        _ => Err(PyValueError::new_err("internal error: static type reached decode_dynamic")),
+    }
+}
+
+fn word_to_pyint(py: Python, word: &[u8], signed: bool) -> PyResult<PyObject> {
+    let bytes = PyBytes::new(py, word);
+    let int_type = py.import("builtins")?.getattr("int")?;
+    let kwargs = PyDict::new(py);
+    kwargs.set_item("signed", signed)?;
+    let result = int_type.call_method("from_bytes", (bytes, "big"), Some(kwargs))?;
+    Ok(result.into())
+}
+
+fn read_word<'a>(data: &'a [u8], at: usize) -> PyResult<&'a [u8]> {
+    let end = at.checked_add(32).ok_or_else(|| PyValueError::new_err("word offset overflow"))?;
+    data.get(at..end)
+        .ok_or_else(|| PyValueError::new_err(format!("truncated data: word at {} out of bounds ({} bytes)", at, data.len())))
+}
+
+fn read_usize(data: &[u8], at: usize) -> PyResult<usize> {
+    let word = read_word(data, at)?;
+    if word[..24].iter().any(|&b| b != 0) {
+        return Err(PyValueError::new_err(format!("offset/length word at {} exceeds usize range", at)));
+    }
+    let mut buf = [0u8; 8];
+    buf.copy_from_slice(&word[24..32]);
+    Ok(u64::from_be_bytes(buf) as usize)
+}
+
+fn read_bytes<'a>(data: &'a [u8], start: usize, len: usize) -> PyResult<&'a [u8]> {
+    let end = start.checked_add(len).ok_or_else(|| PyValueError::new_err("byte range overflow"))?;
+    data.get(start..end)
+        .ok_or_else(|| PyValueError::new_err(format!("truncated data: {} bytes at {} out of bounds ({} bytes)", len, start, data.len())))
+}
+
+// A dynamic array's element count comes straight from attacker-controlled
+// calldata, so it must be bounds-checked before it's used to size a `Vec` -
+// an unvalidated `len` (e.g. from a crafted offset/length word) can make the
+// allocator abort the whole process, which isn't even catchable like a
+// panic. Every element takes at least one 32-byte word, so this is a cheap
+// lower bound on how much data the array could possibly need, mirroring
+// what `read_bytes` already enforces for `bytes`/`str`.
+fn check_array_len(data: &[u8], elements_start: usize, len: usize) -> PyResult<()> {
+    let min_bytes = len.checked_mul(32).ok_or_else(|| PyValueError::new_err("array length overflow"))?;
+    let end = elements_start
+        .checked_add(min_bytes)
+        .ok_or_else(|| PyValueError::new_err("array bounds overflow"))?;
+    if end > data.len() {
+        return Err(PyValueError::new_err(format!(
+            "truncated data: array of {} elements at {} needs at least {} bytes, have {}",
+            len, elements_start, min_bytes, data.len()
+        )));
+    }
+    Ok(())
+}
+
+fn checksum_address(addr: &[u8]) -> String {
+    let hex_addr = hex::encode(addr);
+    let hash = keccak256(hex_addr.as_bytes());
+
+    let mut result = String::with_capacity(42);
+    result.push_str("0x");
+    for (i, c) in hex_addr.chars().enumerate() {
+        if c.is_ascii_digit() {
+            result.push(c);
+            continue;
+        }
+        let nibble = if i % 2 == 0 { hash[i / 2] >> 4 } else { hash[i / 2] & 0x0f };
+        if nibble >= 8 {
+            result.push(c.to_ascii_uppercase());
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+fn keccak256(bytes: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak::v256();
+    hasher.update(bytes);
+    let mut output = [0u8; 32];
+    hasher.finalize(&mut output);
+    output
+}
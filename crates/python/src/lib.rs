@@ -1,11 +1,13 @@
-use alloy_json_abi::{Function, EventParam, Param, StateMutability};
+mod codec;
+
+use alloy_json_abi::{Function, EventParam, JsonAbi, Param, StateMutability};
 use heimdall_decompiler::{decompile, DecompilerArgsBuilder};
 use indexmap::IndexMap;
 use pyo3::exceptions::{PyRuntimeError, PyTimeoutError, PyIOError, PyValueError};
 use pyo3::prelude::*;
-use pyo3::types::PyBytes;
+use pyo3::types::{PyBytes, PyDict, PyList};
 use serde::{Serialize, Deserialize};
-use serde_json::Value;
+use serde_json::{json, Value};
 use std::fs;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -66,6 +68,9 @@ struct ABIEvent {
     inputs: Vec<ABIEventParam>,
     #[pyo3(get)]
     anonymous: bool,
+
+    signature: String,
+    topic0: [u8; 32],
 }
 
 #[pyclass(module = "heimdall_py")]
@@ -80,20 +85,27 @@ struct ABIError {
 #[pyclass(module = "heimdall_py")]
 #[derive(Clone, Serialize, Deserialize)]
 struct StorageSlot {
+    // Full 256-bit slot index (decimal), since keccak-derived mapping/dynamic
+    // array slots don't fit in a u64.
     #[pyo3(get, set)]
-    index: u64,
+    index: String,
     #[pyo3(get, set)]
     offset: u32,
     #[pyo3(get, set)]
     typ: String,
+    // "plain" | "mapping" | "dynamic_array" | "packed"
+    #[pyo3(get, set)]
+    kind: String,
+    #[pyo3(get, set)]
+    mapping_key_type: Option<String>,
 }
 
 #[pymethods]
 impl StorageSlot {
     #[new]
-    #[pyo3(signature = (index=0, offset=0, typ=String::new()))]
-    fn new(index: u64, offset: u32, typ: String) -> Self {
-        StorageSlot { index, offset, typ }
+    #[pyo3(signature = (index="0".to_string(), offset=0, typ=String::new(), kind="plain".to_string(), mapping_key_type=None))]
+    fn new(index: String, offset: u32, typ: String, kind: String, mapping_key_type: Option<String>) -> Self {
+        StorageSlot { index, offset, typ, kind, mapping_key_type }
     }
 }
 
@@ -115,9 +127,10 @@ struct ABI {
     
     #[pyo3(get, set)]
     storage_layout: Vec<StorageSlot>,
-    
+
     by_selector: IndexMap<[u8; 4], usize>,
     by_name: IndexMap<String, usize>,
+    by_topic0: IndexMap<[u8; 32], usize>,
 }
 
 fn convert_param(param: &Param) -> ABIParam {
@@ -178,6 +191,136 @@ fn collapse_if_tuple(component: &Value) -> PyResult<String> {
     Ok(format!("({}){}", delimited, array_dim))
 }
 
+// Reverses `collapse_if_tuple`: given a (possibly collapsed) type string
+// like `(uint256,address)[]`, splits the parenthesized list respecting
+// nesting depth and rebuilds the `type: "tuple[]"` + `components` shape
+// standard Solidity ABI JSON expects. Component names aren't recoverable
+// from the collapsed string (only types survive collapsing), so rebuilt
+// components carry an empty `name`.
+fn expand_tuple_type(type_str: &str) -> PyResult<(String, Option<Value>)> {
+    let type_str = type_str.trim();
+    if !type_str.starts_with('(') {
+        return Ok((type_str.to_string(), None));
+    }
+
+    let close = codec::find_matching_paren(type_str)?;
+    let suffix = &type_str[close + 1..];
+    let components: Vec<Value> = codec::split_top_level(&type_str[1..close])
+        .iter()
+        .map(|part| {
+            let (child_type, child_components) = expand_tuple_type(part)?;
+            let mut comp = json!({ "name": "", "type": child_type });
+            if let Some(cc) = child_components {
+                comp["components"] = cc;
+            }
+            Ok(comp)
+        })
+        .collect::<PyResult<_>>()?;
+
+    Ok((format!("tuple{}", suffix), Some(Value::Array(components))))
+}
+
+fn param_to_json(param: &ABIParam) -> PyResult<Value> {
+    let (type_, components) = expand_tuple_type(&param.type_)?;
+    let mut v = json!({ "name": param.name, "type": type_ });
+    if let Some(internal_type) = &param.internal_type {
+        v["internalType"] = json!(internal_type);
+    }
+    if let Some(components) = components {
+        v["components"] = components;
+    }
+    Ok(v)
+}
+
+fn event_param_to_json(param: &ABIEventParam) -> PyResult<Value> {
+    let (type_, components) = expand_tuple_type(&param.type_)?;
+    let mut v = json!({ "name": param.name, "type": type_, "indexed": param.indexed });
+    if let Some(internal_type) = &param.internal_type {
+        v["internalType"] = json!(internal_type);
+    }
+    if let Some(components) = components {
+        v["components"] = components;
+    }
+    Ok(v)
+}
+
+fn function_entry_json(func: &ABIFunction) -> PyResult<Value> {
+    Ok(json!({
+        "type": "function",
+        "name": func.name,
+        "inputs": func.inputs.iter().map(param_to_json).collect::<PyResult<Vec<_>>>()?,
+        "outputs": func.outputs.iter().map(param_to_json).collect::<PyResult<Vec<_>>>()?,
+        "stateMutability": func.state_mutability,
+    }))
+}
+
+fn constructor_entry_json(func: &ABIFunction) -> PyResult<Value> {
+    Ok(json!({
+        "type": "constructor",
+        "inputs": func.inputs.iter().map(param_to_json).collect::<PyResult<Vec<_>>>()?,
+        "stateMutability": func.state_mutability,
+    }))
+}
+
+fn fallback_entry_json(func: &ABIFunction) -> Value {
+    json!({ "type": "fallback", "stateMutability": func.state_mutability })
+}
+
+fn receive_entry_json() -> Value {
+    json!({ "type": "receive", "stateMutability": "payable" })
+}
+
+fn event_entry_json(event: &ABIEvent) -> PyResult<Value> {
+    Ok(json!({
+        "type": "event",
+        "name": event.name,
+        "inputs": event.inputs.iter().map(event_param_to_json).collect::<PyResult<Vec<_>>>()?,
+        "anonymous": event.anonymous,
+    }))
+}
+
+fn error_entry_json(error: &ABIError) -> PyResult<Value> {
+    Ok(json!({
+        "type": "error",
+        "name": error.name,
+        "inputs": error.inputs.iter().map(param_to_json).collect::<PyResult<Vec<_>>>()?,
+    }))
+}
+
+// Converts a parsed `serde_json::Value` into the equivalent Python object
+// (dict/list/str/int/float/bool/None), for exposing `to_dict()` without
+// pulling in a JSON<->Python bridging dependency.
+fn json_value_to_py(py: Python, value: &Value) -> PyResult<PyObject> {
+    Ok(match value {
+        Value::Null => py.None(),
+        Value::Bool(b) => b.into_py(py),
+        Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                i.into_py(py)
+            } else if let Some(u) = n.as_u64() {
+                u.into_py(py)
+            } else {
+                n.as_f64().unwrap_or(0.0).into_py(py)
+            }
+        }
+        Value::String(s) => s.into_py(py),
+        Value::Array(items) => {
+            let converted = items
+                .iter()
+                .map(|item| json_value_to_py(py, item))
+                .collect::<PyResult<Vec<_>>>()?;
+            PyList::new(py, converted).into()
+        }
+        Value::Object(map) => {
+            let dict = PyDict::new(py);
+            for (key, val) in map {
+                dict.set_item(key, json_value_to_py(py, val)?)?;
+            }
+            dict.into()
+        }
+    })
+}
+
 fn parse_param(param: &Value) -> PyResult<ABIParam> {
     let name = param.get("name")
         .and_then(|v| v.as_str())
@@ -222,7 +365,23 @@ fn parse_event_param(param: &Value) -> PyResult<ABIEventParam> {
 }
 
 fn compute_selector(name: &str, input_types: &[String]) -> [u8; 4] {
+    let mut selector = [0u8; 4];
+    selector.copy_from_slice(&compute_event_topic0(name, input_types)[..4]);
+    selector
+}
+
+// Same keccak path as `compute_selector`, but keeps all 32 bytes - events are
+// matched on the full topic hash rather than a 4-byte selector.
+fn compute_event_topic0(name: &str, input_types: &[String]) -> [u8; 32] {
     let signature = format!("{}({})", name, input_types.join(","));
+    let mut hasher = Keccak::v256();
+    hasher.update(signature.as_bytes());
+    let mut output = [0u8; 32];
+    hasher.finalize(&mut output);
+    output
+}
+
+fn selector_from_signature(signature: &str) -> [u8; 4] {
     let mut hasher = Keccak::v256();
     hasher.update(signature.as_bytes());
     let mut output = [0u8; 32];
@@ -232,6 +391,58 @@ fn compute_selector(name: &str, input_types: &[String]) -> [u8; 4] {
     selector
 }
 
+// Splits a `name(type1,type2)` signature string into its name and argument
+// types, respecting nested-tuple parentheses via the same top-level
+// splitter the ABI codec uses for collapsed tuple type strings.
+fn parse_signature(signature: &str) -> PyResult<(String, Vec<String>)> {
+    let open = signature
+        .find('(')
+        .ok_or_else(|| PyValueError::new_err(format!("invalid signature: {}", signature)))?;
+    if !signature.ends_with(')') {
+        return Err(PyValueError::new_err(format!("invalid signature: {}", signature)));
+    }
+
+    let name = signature[..open].to_string();
+    let inner = &signature[open + 1..signature.len() - 1];
+    let types = if inner.is_empty() {
+        Vec::new()
+    } else {
+        codec::split_top_level(inner)
+    };
+    Ok((name, types))
+}
+
+/// Loads a selector-resolution database from `path`: either a JSON array of
+/// signature strings, or a line-oriented text file with one signature per
+/// line (blank lines ignored).
+fn load_signature_db(path: &str) -> PyResult<Vec<String>> {
+    let contents = fs::read_to_string(path)
+        .map_err(|e| PyIOError::new_err(format!("Failed to read signature db {}: {}", path, e)))?;
+
+    if contents.trim_start().starts_with('[') {
+        let value: Value = serde_json::from_str(&contents)
+            .map_err(|e| PyValueError::new_err(format!("Invalid JSON in signature db: {}", e)))?;
+        let array = value
+            .as_array()
+            .ok_or_else(|| PyValueError::new_err("Expected a JSON array of signature strings"))?;
+        array
+            .iter()
+            .map(|v| {
+                v.as_str()
+                    .map(|s| s.to_string())
+                    .ok_or_else(|| PyValueError::new_err("signature db entries must be strings"))
+            })
+            .collect()
+    } else {
+        Ok(contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect())
+    }
+}
+
 fn parse_function_entry(entry: &Value) -> PyResult<Option<ABIFunction>> {
     let name = entry.get("name")
         .and_then(|v| v.as_str())
@@ -303,9 +514,12 @@ fn parse_event_entry(entry: &Value) -> PyResult<Option<ABIEvent>> {
         .and_then(|v| v.as_array());
 
     let mut inputs = Vec::new();
+    let mut input_types = Vec::new();
     if let Some(inputs_json) = inputs_json {
         for input in inputs_json {
-            inputs.push(parse_event_param(input)?);
+            let param = parse_event_param(input)?;
+            input_types.push(param.type_.clone());
+            inputs.push(param);
         }
     }
 
@@ -313,10 +527,15 @@ fn parse_event_entry(entry: &Value) -> PyResult<Option<ABIEvent>> {
         .and_then(|v| v.as_bool())
         .unwrap_or(false);
 
+    let signature = format!("{}({})", name, input_types.join(","));
+    let topic0 = compute_event_topic0(&name, &input_types);
+
     Ok(Some(ABIEvent {
         name,
         inputs,
         anonymous,
+        signature,
+        topic0,
     }))
 }
 
@@ -434,9 +653,27 @@ impl ABIFunction {
     fn output_types(&self) -> Vec<String> {
         self.outputs.iter().map(|p| p.type_.clone()).collect()
     }
+
+    /// Decodes ABI-encoded return data against this function's `outputs`,
+    /// returning a list of Python-native values.
+    fn decode_output(&self, py: Python, data: &[u8]) -> PyResult<PyObject> {
+        codec::decode_function_output(py, self, data)
+    }
 }
 
 
+#[pymethods]
+impl ABIEvent {
+    fn signature(&self) -> String {
+        self.signature.clone()
+    }
+
+    #[getter]
+    fn topic0(&self) -> Vec<u8> {
+        self.topic0.to_vec()
+    }
+}
+
 #[pymethods]
 impl ABI {
     #[new]
@@ -451,6 +688,7 @@ impl ABI {
             storage_layout: Vec::new(),
             by_selector: IndexMap::new(),
             by_name: IndexMap::new(),
+            by_topic0: IndexMap::new(),
         }
     }
 
@@ -498,6 +736,8 @@ impl ABI {
                 },
                 "event" => {
                     if let Some(event) = parse_event_entry(entry)? {
+                        let idx = abi.events.len();
+                        abi.by_topic0.insert(event.topic0, idx);
                         abi.events.push(event);
                     }
                 },
@@ -557,7 +797,72 @@ impl ABI {
         
         Ok(None)
     }
-    
+
+    /// Encodes `args` against the function identified by `name_or_selector`
+    /// (accepts the same key forms as `get_function`: a function name, a
+    /// `"0x..."` hex selector, or raw selector bytes), prefixed with its
+    /// 4-byte selector.
+    fn encode_function_input(&self, py: Python, name_or_selector: &PyAny, args: &PyAny) -> PyResult<Vec<u8>> {
+        let function = self
+            .get_function(py, name_or_selector)?
+            .ok_or_else(|| PyValueError::new_err("no function matches name_or_selector"))?;
+        codec::encode_function_input(py, &function, args)
+    }
+
+    /// Reads the 4-byte selector off `data`, matches it against
+    /// `by_selector`, and decodes the remainder against that function's
+    /// `inputs`. Returns `(function, args)`.
+    fn decode_function_input(&self, py: Python, data: &[u8]) -> PyResult<(ABIFunction, PyObject)> {
+        codec::decode_function_input(py, self, data)
+    }
+
+    /// Matches `topics[0]` against `by_topic0`, decodes indexed parameters
+    /// out of the remaining topics and non-indexed parameters out of `data`,
+    /// and returns `(event, {param_name: value})`.
+    fn decode_log(&self, py: Python, topics: Vec<Vec<u8>>, data: &[u8]) -> PyResult<(ABIEvent, PyObject)> {
+        codec::decode_log(py, self, topics, data)
+    }
+
+    /// Loads a local selector->signature database from `db_path` (see
+    /// `load_signature_db`) and rewrites any function whose name is still
+    /// `Unresolved_xxxxxxxx` to the matched human-readable name and inputs,
+    /// rebuilding `by_name`/`by_selector`. Returns how many functions were
+    /// resolved.
+    fn resolve_names(&mut self, db_path: String) -> PyResult<usize> {
+        let mut by_selector_sig = IndexMap::new();
+        for signature in load_signature_db(&db_path)? {
+            by_selector_sig.insert(selector_from_signature(&signature), signature);
+        }
+
+        let mut resolved = 0;
+        for func in &mut self.functions {
+            if !func.name.starts_with("Unresolved_") {
+                continue;
+            }
+            if let Some(signature) = by_selector_sig.get(&func.selector) {
+                let (name, types) = parse_signature(signature)?;
+                func.inputs = types
+                    .into_iter()
+                    .map(|type_| ABIParam { name: String::new(), type_, internal_type: None })
+                    .collect();
+                func.name = name;
+                func.signature = signature.clone();
+                resolved += 1;
+            }
+        }
+
+        self.by_selector = IndexMap::new();
+        self.by_name = IndexMap::new();
+        for (idx, func) in self.functions.iter().enumerate() {
+            self.by_selector.insert(func.selector, idx);
+            if !func.name.is_empty() {
+                self.by_name.insert(func.name.clone(), idx);
+            }
+        }
+
+        Ok(resolved)
+    }
+
     fn __getstate__(&self, py: Python) -> PyResult<PyObject> {
         let state = (
             &self.functions,
@@ -569,16 +874,17 @@ impl ABI {
             &self.storage_layout,
             &self.by_selector,
             &self.by_name,
+            &self.by_topic0,
         );
-        
+
         let bytes = bincode::serialize(&state)
             .map_err(|e| PyRuntimeError::new_err(format!("Serialization failed: {}", e)))?;
         Ok(PyBytes::new(py, &bytes).into())
     }
-    
+
     fn __setstate__(&mut self, state: &PyBytes) -> PyResult<()> {
         let bytes = state.as_bytes();
-        
+
         type StateType = (
             Vec<ABIFunction>,
             Vec<ABIEvent>,
@@ -589,12 +895,13 @@ impl ABI {
             Vec<StorageSlot>,
             IndexMap<[u8; 4], usize>,
             IndexMap<String, usize>,
+            IndexMap<[u8; 32], usize>,
         );
-        
-        let (functions, events, errors, constructor, fallback, receive, storage_layout, by_selector, by_name): StateType = 
+
+        let (functions, events, errors, constructor, fallback, receive, storage_layout, by_selector, by_name, by_topic0): StateType =
             bincode::deserialize(bytes)
                 .map_err(|e| PyRuntimeError::new_err(format!("Deserialization failed: {}", e)))?;
-        
+
         *self = ABI {
             functions,
             events,
@@ -605,8 +912,9 @@ impl ABI {
             storage_layout,
             by_selector,
             by_name,
+            by_topic0,
         };
-        
+
         Ok(())
     }
     
@@ -623,6 +931,44 @@ impl ABI {
             self.storage_layout.len()
         )
     }
+
+    /// Re-serializes this ABI into the standard Solidity ABI JSON array
+    /// form, reversing `collapse_if_tuple` to reconstruct `components`.
+    fn to_json(&self) -> PyResult<String> {
+        serde_json::to_string(&self.to_json_value()?)
+            .map_err(|e| PyRuntimeError::new_err(format!("Serialization failed: {}", e)))
+    }
+
+    /// Same as `to_json`, but returns Python-native dicts/lists instead of
+    /// a JSON string.
+    fn to_dict(&self, py: Python) -> PyResult<PyObject> {
+        json_value_to_py(py, &self.to_json_value()?)
+    }
+}
+
+impl ABI {
+    fn to_json_value(&self) -> PyResult<Value> {
+        let mut entries = Vec::new();
+        if let Some(constructor) = &self.constructor {
+            entries.push(constructor_entry_json(constructor)?);
+        }
+        for func in &self.functions {
+            entries.push(function_entry_json(func)?);
+        }
+        for event in &self.events {
+            entries.push(event_entry_json(event)?);
+        }
+        for error in &self.errors {
+            entries.push(error_entry_json(error)?);
+        }
+        if let Some(fallback) = &self.fallback {
+            entries.push(fallback_entry_json(fallback));
+        }
+        if self.receive.is_some() {
+            entries.push(receive_entry_json());
+        }
+        Ok(Value::Array(entries))
+    }
 }
 
 fn convert_function(func: &Function) -> ABIFunction {
@@ -648,8 +994,8 @@ fn convert_function(func: &Function) -> ABIFunction {
 }
 
 #[pyfunction]
-#[pyo3(signature = (code, skip_resolving=false, rpc_url=None, timeout_secs=None))]
-fn decompile_code(_py: Python<'_>, code: String, skip_resolving: bool, rpc_url: Option<String>, timeout_secs: Option<u64>) -> PyResult<ABI> {
+#[pyo3(signature = (code, skip_resolving=false, rpc_url=None, timeout_secs=None, signature_db=None))]
+fn decompile_code(_py: Python<'_>, code: String, skip_resolving: bool, rpc_url: Option<String>, timeout_secs: Option<u64>, signature_db: Option<String>) -> PyResult<ABI> {
     let timeout_ms = timeout_secs.unwrap_or(25).saturating_mul(1000);
     let timeout_duration = Duration::from_millis(timeout_ms);
     let args = DecompilerArgsBuilder::new()
@@ -703,23 +1049,37 @@ fn decompile_code(_py: Python<'_>, code: String, skip_resolving: bool, rpc_url:
             )))
         }
     }?;
-    
-    let json_abi = result.abi;
-    
+
+    let mut abi = abi_from_json_abi(&result.abi);
+    if let Some(db_path) = signature_db {
+        abi.resolve_names(db_path)?;
+    }
+    Ok(abi)
+}
+
+/// Assembles an `ABI` (with its `by_selector`/`by_name`/`by_topic0` indices)
+/// from a decompiled `alloy_json_abi::JsonAbi`. Shared by the blocking and
+/// async decompilation entry points.
+fn abi_from_json_abi(json_abi: &JsonAbi) -> ABI {
     let functions: Vec<ABIFunction> = json_abi
         .functions()
         .map(convert_function)
         .collect();
-    
+
     let events: Vec<ABIEvent> = json_abi
         .events()
-        .map(|event| ABIEvent {
-            name: event.name.clone(),
-            inputs: event.inputs.iter().map(convert_event_param).collect(),
-            anonymous: event.anonymous,
+        .map(|event| {
+            let input_types: Vec<String> = event.inputs.iter().map(|p| p.ty.clone()).collect();
+            ABIEvent {
+                name: event.name.clone(),
+                inputs: event.inputs.iter().map(convert_event_param).collect(),
+                anonymous: event.anonymous,
+                signature: format!("{}({})", event.name, input_types.join(",")),
+                topic0: compute_event_topic0(&event.name, &input_types),
+            }
         })
         .collect();
-    
+
     let errors: Vec<ABIError> = json_abi
         .errors()
         .map(|error| ABIError {
@@ -727,9 +1087,9 @@ fn decompile_code(_py: Python<'_>, code: String, skip_resolving: bool, rpc_url:
             inputs: error.inputs.iter().map(convert_param).collect(),
         })
         .collect();
-    
+
     let constructor = json_abi.constructor.as_ref().map(|c| {
-        let signature = format!("constructor({})", 
+        let signature = format!("constructor({})",
             c.inputs.iter()
                 .map(|p| p.ty.as_str())
                 .collect::<Vec<_>>()
@@ -745,7 +1105,7 @@ fn decompile_code(_py: Python<'_>, code: String, skip_resolving: bool, rpc_url:
             signature,
         }
     });
-    
+
     let fallback = json_abi.fallback.as_ref().map(|f| ABIFunction {
         name: "fallback".to_string(),
         inputs: Vec::new(),
@@ -756,7 +1116,7 @@ fn decompile_code(_py: Python<'_>, code: String, skip_resolving: bool, rpc_url:
         selector: [0; 4],
         signature: "fallback()".to_string(),
     });
-    
+
     let receive = json_abi.receive.as_ref().map(|_| ABIFunction {
         name: "receive".to_string(),
         inputs: Vec::new(),
@@ -767,18 +1127,23 @@ fn decompile_code(_py: Python<'_>, code: String, skip_resolving: bool, rpc_url:
         selector: [0; 4],
         signature: "receive()".to_string(),
     });
-    
+
     let mut by_selector = IndexMap::new();
     let mut by_name = IndexMap::new();
-    
+
     for (idx, func) in functions.iter().enumerate() {
         by_selector.insert(func.selector, idx);
         if !func.name.is_empty() {
             by_name.insert(func.name.clone(), idx);
         }
     }
-    
-    let abi = ABI {
+
+    let mut by_topic0 = IndexMap::new();
+    for (idx, event) in events.iter().enumerate() {
+        by_topic0.insert(event.topic0, idx);
+    }
+
+    ABI {
         functions,
         events,
         errors,
@@ -788,9 +1153,149 @@ fn decompile_code(_py: Python<'_>, code: String, skip_resolving: bool, rpc_url:
         storage_layout: Vec::new(),
         by_selector,
         by_name,
-    };
-    
-    Ok(abi)
+        by_topic0,
+    }
+}
+
+/// Aborts the in-flight decompilation task when dropped, so cancelling the
+/// Python awaitable from `decompile_code_async` (or the asyncio event loop
+/// timing it out) propagates into the shared runtime rather than leaving
+/// the task to run to completion unobserved.
+struct AbortGuard(tokio::task::AbortHandle);
+
+impl Drop for AbortGuard {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (code, skip_resolving=false, rpc_url=None, timeout_secs=None))]
+fn decompile_code_async(
+    py: Python<'_>,
+    code: String,
+    skip_resolving: bool,
+    rpc_url: Option<String>,
+    timeout_secs: Option<u64>,
+) -> PyResult<&PyAny> {
+    let timeout_ms = timeout_secs.unwrap_or(25).saturating_mul(1000);
+    let args = DecompilerArgsBuilder::new()
+        .target(code)
+        .rpc_url(rpc_url.unwrap_or_default())
+        .default(true)
+        .skip_resolving(skip_resolving)
+        .include_solidity(false)
+        .include_yul(false)
+        .output(String::new())
+        .timeout(timeout_ms)
+        .build()
+        .map_err(|e| PyRuntimeError::new_err(format!("Failed to build args: {}", e)))?;
+
+    pyo3_asyncio::tokio::future_into_py(py, async move {
+        // Spawned onto pyo3-asyncio's shared runtime, unlike `decompile_code`
+        // which spins up a fresh one per call. The `AbortHandle` lets
+        // cancellation reach the task even after it's detached from this
+        // future.
+        let handle = pyo3_asyncio::tokio::get_runtime().spawn(decompile(args));
+        let _abort_guard = AbortGuard(handle.abort_handle());
+
+        match handle.await {
+            Ok(Ok(result)) => Ok(abi_from_json_abi(&result.abi)),
+            Ok(Err(e)) => Err(PyRuntimeError::new_err(format!("Decompilation failed: {}", e))),
+            Err(e) if e.is_cancelled() => Err(PyRuntimeError::new_err("Decompilation was cancelled")),
+            Err(e) => Err(PyRuntimeError::new_err(format!("Decompilation task panicked: {}", e))),
+        }
+    })
+}
+
+fn exception_to_pyobject(py: Python, err: PyErr) -> PyObject {
+    err.value(py).into_py(py)
+}
+
+/// Decompiles a batch of bytecode/address `targets` concurrently on a
+/// single shared Tokio runtime, bounding in-flight work to
+/// `max_concurrency` and applying `timeout_secs` per target rather than to
+/// the batch as a whole. Results are collected positionally - a timeout or
+/// failure on one target stores a `TimeoutError`/`RuntimeError` in that
+/// slot instead of aborting the rest of the batch. The GIL is released for
+/// the entire batch so other Python threads keep running while it awaits.
+#[pyfunction]
+#[pyo3(signature = (targets, skip_resolving=false, rpc_url=None, timeout_secs=None, max_concurrency=None))]
+fn decompile_many(
+    py: Python<'_>,
+    targets: Vec<String>,
+    skip_resolving: bool,
+    rpc_url: Option<String>,
+    timeout_secs: Option<u64>,
+    max_concurrency: Option<usize>,
+) -> PyResult<Vec<PyObject>> {
+    let timeout_ms = timeout_secs.unwrap_or(25).saturating_mul(1000);
+    let timeout_duration = Duration::from_millis(timeout_ms);
+    let max_concurrency = max_concurrency.unwrap_or_else(num_cpus::get).max(1);
+
+    let mut built_args = Vec::with_capacity(targets.len());
+    for target in targets {
+        built_args.push(
+            DecompilerArgsBuilder::new()
+                .target(target)
+                .rpc_url(rpc_url.clone().unwrap_or_default())
+                .default(true)
+                .skip_resolving(skip_resolving)
+                .include_solidity(false)
+                .include_yul(false)
+                .output(String::new())
+                .timeout(timeout_ms)
+                .build()
+                .map_err(|e| format!("Failed to build args: {}", e)),
+        );
+    }
+
+    let results: Vec<Result<ABI, String>> = py.allow_threads(|| {
+        pyo3_asyncio::tokio::get_runtime().block_on(async move {
+            let semaphore = Arc::new(tokio::sync::Semaphore::new(max_concurrency));
+            let tasks: Vec<_> = built_args
+                .into_iter()
+                .map(|args_result| {
+                    let semaphore = semaphore.clone();
+                    tokio::spawn(async move {
+                        let args = args_result?;
+                        let _permit = semaphore
+                            .acquire_owned()
+                            .await
+                            .map_err(|e| e.to_string())?;
+                        match tokio::time::timeout(timeout_duration, decompile(args)).await {
+                            Ok(Ok(result)) => Ok(abi_from_json_abi(&result.abi)),
+                            Ok(Err(e)) => Err(format!("Decompilation failed: {}", e)),
+                            Err(_) => Err(format!(
+                                "Decompilation timed out after {} seconds",
+                                timeout_ms / 1000
+                            )),
+                        }
+                    })
+                })
+                .collect();
+
+            let mut results = Vec::with_capacity(tasks.len());
+            for task in tasks {
+                results.push(match task.await {
+                    Ok(result) => result,
+                    Err(e) => Err(format!("Decompilation task panicked: {}", e)),
+                });
+            }
+            results
+        })
+    });
+
+    Ok(results
+        .into_iter()
+        .map(|result| match result {
+            Ok(abi) => abi.into_py(py),
+            Err(message) if message.contains("timed out") => {
+                exception_to_pyobject(py, PyTimeoutError::new_err(message))
+            }
+            Err(message) => exception_to_pyobject(py, PyRuntimeError::new_err(message)),
+        })
+        .collect())
 }
 
 #[pymodule]
@@ -803,5 +1308,7 @@ fn heimdall_py(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_class::<StorageSlot>()?;
     m.add_class::<ABI>()?;
     m.add_function(wrap_pyfunction!(decompile_code, m)?)?;
+    m.add_function(wrap_pyfunction!(decompile_code_async, m)?)?;
+    m.add_function(wrap_pyfunction!(decompile_many, m)?)?;
     Ok(())
 }
\ No newline at end of file